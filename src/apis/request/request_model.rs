@@ -1,18 +1,66 @@
 use serde::Deserialize;
-use utoipa::IntoParams;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::models::{WebhookDeliveryStatus, WebhookEventType};
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct GetERC20BalanceRequest {
     pub chain_id: u8,
     pub contract_type: String,
     pub public_address: String,
+    // value the balance in usd by the configured price pair.
+    pub include_usd: Option<bool>,
+    // latest, safe, finalized or a number of confirmations, reads are pinned to that block.
+    pub finality: Option<String>,
 }
 
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct GetNFTOwnershipRequest {
     pub chain_id: u8,
-    pub game_client: u8,
+    // collection id or contract address, takes precedence over `game_client`.
+    pub collection: Option<String>,
+    // collection of the game client, the default collection of the network when both are empty.
+    pub game_client: Option<u8>,
     pub public_address: String,
+    // rewrite metadata image to the proxied image url.
+    pub proxy_image: Option<bool>,
+    // filter owned items like the catalogue, see `GetNFTCatalogueRequest`.
+    pub traits: Option<String>,
+    pub nft_type1: Option<String>,
+    pub nft_type2: Option<String>,
+    pub name: Option<String>,
+    // latest, safe, finalized or a number of confirmations, reads are pinned to that block.
+    pub finality: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GetERC20BalanceBatchRequest {
+    pub chain_id: u8,
+    pub contract_type: String,
+    pub public_addresses: Vec<String>,
+    // value the balances in usd by the configured price pair.
+    pub include_usd: Option<bool>,
+    // latest, safe, finalized or a number of confirmations, reads are pinned to that block.
+    pub finality: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GetNFTOwnershipBatchRequest {
+    pub chain_id: u8,
+    // collection id or contract address, takes precedence over `game_client`.
+    pub collection: Option<String>,
+    pub game_client: Option<u8>,
+    pub public_addresses: Vec<String>,
+    // latest, safe, finalized or a number of confirmations, reads are pinned to that block.
+    pub finality: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GetNECOStakedInfoBatchRequest {
+    pub chain_id: u64,
+    pub public_addresses: Vec<String>,
+    // latest, safe, finalized or a number of confirmations, reads are pinned to that block.
+    pub finality: Option<String>,
 }
 
 #[derive(Debug, Deserialize, IntoParams)]
@@ -26,3 +74,177 @@ pub struct GetERC1155NFTMetadataRequest {
     pub chain_id: u8,
     pub nft_id: String,
 }
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GetNFTMetadataBatchRequest {
+    pub chain_id: u8,
+    // metadata of all known nft ids when it is empty.
+    pub nft_ids: Option<Vec<String>>,
+    // rewrite metadata image to the proxied image url.
+    pub proxy_image: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct UsdValueOption {
+    // value native and erc20 balances in usd by the configured price pairs.
+    pub include_usd: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct FinalityOption {
+    // latest, safe, finalized or a number of confirmations, reads are pinned to that block.
+    pub finality: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ImageProxyOption {
+    // rewrite metadata image to the proxied image url.
+    pub proxy_image: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetNFTImageRequest {
+    // thumbnail size in pixels, the original size when it is empty.
+    pub size: Option<u32>,
+    // "webp" or "png", default is "webp".
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetLockedNFTsRequest {
+    pub chain_id: u8,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetNFTPermissionRequest {
+    pub chain_id: u8,
+    pub public_address: String,
+    // operator to check `isApprovedForAll` against, e.g. the marketplace contract.
+    pub operator: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetNFTCollectionRequest {
+    pub chain_id: u8,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetNFTHoldersRequest {
+    pub chain_id: u8,
+    // list holders of one nft id, or of the whole collection when it is empty.
+    pub nft_id: Option<String>,
+    // "amount" or "address", default is "amount".
+    pub sort_by: Option<String>,
+    // "asc" or "desc", default is "desc".
+    pub order: Option<String>,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterWebhookRequest {
+    pub url: String,
+    pub chain_id: u8,
+    // erc20 token, erc1155 collection, or the staked token of stake events.
+    pub contract_address: String,
+    pub event_type: WebhookEventType,
+    // deliver only events sent or received by the addresses.
+    pub public_addresses: Option<Vec<String>>,
+    pub confirmations: Option<u64>,
+    // key of the HMAC-SHA256 signature of payloads.
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct WebhookIdParam {
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetWebhookDeliveriesRequest {
+    pub status: Option<WebhookDeliveryStatus>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PurgeMetadataCacheRequest {
+    pub chain_id: u8,
+    // purge one nft id, or all nft ids on the network when it is empty.
+    pub nft_id: Option<String>,
+    // purge an ERC721 collection, the game item nft when it is empty.
+    pub contract_address: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetNFTCatalogueRequest {
+    pub chain_id: u8,
+    // trait filters as `type:value,type:value`, e.g. `rarity:rare,rarity:epic`.
+    pub traits: Option<String>,
+    pub nft_type1: Option<String>,
+    pub nft_type2: Option<String>,
+    // case-insensitive substring of the name.
+    pub name: Option<String>,
+    // "nft_id" or "name", default is "nft_id".
+    pub sort_by: Option<String>,
+    // "asc" or "desc", default is "desc".
+    pub order: Option<String>,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetNFTFacetsRequest {
+    pub chain_id: u8,
+    // same filters as the catalogue, facets count the matching items only.
+    pub traits: Option<String>,
+    pub nft_type1: Option<String>,
+    pub nft_type2: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetERC721OwnershipRequest {
+    pub chain_id: u8,
+    pub contract_address: String,
+    pub public_address: String,
+    // block to scan transfer events from when the contract is not enumerable, not before
+    // `start_block` of the configured collection.
+    pub from_block: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetLPPositionRequest {
+    pub chain_id: u8,
+    // pair id or pair address configured in `[[liquidity_pairs]]`.
+    pub pair: String,
+    pub public_address: String,
+    // value the underlying tokens in usd by the configured price pairs.
+    pub include_usd: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetWalletStreamRequest {
+    pub chain_id: u8,
+    // comma separated addresses or names.
+    pub public_addresses: String,
+    // replay changes since the block, `Last-Event-ID` of a reconnect takes precedence.
+    pub from_block: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetTransactionStatusRequest {
+    pub chain_id: u8,
+    pub tx_hash: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetERC721TokenRequest {
+    pub chain_id: u8,
+    pub contract_address: String,
+    pub token_id: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct GetWalletInventoryRequest {
+    pub public_address: String,
+}
@@ -1,6 +1,14 @@
 use crate::{
-    models::EmptyData,
-    models::{ERC20Token, NamiLandERC1155NFTMetadata, NamiLandNFTOwnership, NamiXStakedInfo},
+    common::request_meta::response_meta,
+    models::{CachePurgeResult, EmptyData, HealthStatus, ResponseMeta},
+    models::{
+        ERC20BalanceBatch, ERC20Token, ERC721Ownership, ERC721TokenOwner, LPPosition,
+        NamiLandERC1155NFTMetadata, NamiLandLockedNFTs, NamiLandNFTCatalogue,
+        NamiLandNFTCollection, NamiLandNFTFacets, NamiLandNFTHolders, NamiLandNFTMetadataBatch,
+        NamiLandNFTOwnership, NamiLandNFTOwnershipBatch, NamiLandNFTPermission, NamiXStakedInfo,
+        NamiXStakedInfoBatch, TokenPrices, TransactionStatus, WalletInventory, WebhookDeliveries,
+        WebhookSubscription, WebhookSubscriptions,
+    },
 };
 use axum::Json;
 use reqwest::StatusCode;
@@ -10,15 +18,40 @@ use utoipa::ToSchema;
 #[derive(Debug, Default, Serialize, ToSchema)]
 #[aliases(
     NECOStakedInfoResponse = Response<NamiXStakedInfo>,
+    NECOStakedInfoBatchResponse = Response<NamiXStakedInfoBatch>,
     ERC20TokenResponse = Response<ERC20Token>,
+    ERC20BalanceBatchResponse = Response<ERC20BalanceBatch>,
     ERC1155OwnershipResponse = Response<NamiLandNFTOwnership>,
+    ERC1155OwnershipBatchResponse = Response<NamiLandNFTOwnershipBatch>,
+    ERC721OwnershipResponse = Response<ERC721Ownership>,
+    ERC721OwnerResponse = Response<ERC721TokenOwner>,
+    ERC721MetadataResponse = Response<NamiLandERC1155NFTMetadata>,
     ERC1155MetadataResponse = Response<NamiLandERC1155NFTMetadata>,
+    ERC1155MetadataBatchResponse = Response<NamiLandNFTMetadataBatch>,
+    ERC1155HoldersResponse = Response<NamiLandNFTHolders>,
+    ERC1155LockedResponse = Response<NamiLandLockedNFTs>,
+    ERC1155PermissionResponse = Response<NamiLandNFTPermission>,
+    ERC1155CollectionResponse = Response<NamiLandNFTCollection>,
+    ERC1155CatalogueResponse = Response<NamiLandNFTCatalogue>,
+    ERC1155FacetsResponse = Response<NamiLandNFTFacets>,
+    CachePurgeResponse = Response<CachePurgeResult>,
+    HealthResponse = Response<HealthStatus>,
+    WalletInventoryResponse = Response<WalletInventory>,
+    TokenPricesResponse = Response<TokenPrices>,
+    LPPositionResponse = Response<LPPosition>,
+    TransactionStatusResponse = Response<TransactionStatus>,
+    WebhookSubscriptionResponse = Response<WebhookSubscription>,
+    WebhookSubscriptionsResponse = Response<WebhookSubscriptions>,
+    WebhookDeliveriesResponse = Response<WebhookDeliveries>,
     ErrorResponse = Response<EmptyData>
 )]
 pub struct Response<T: Serialize> {
     pub status: u16,
     pub message: String,
     pub data: Option<T>,
+    // only present on successful responses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ResponseMeta>,
 }
 
 impl<T: Serialize + ToSchema> Response<T> {
@@ -27,6 +60,7 @@ impl<T: Serialize + ToSchema> Response<T> {
             status: status.as_u16(),
             message: message.to_string(),
             data: None,
+            meta: None,
         })
     }
 
@@ -35,6 +69,7 @@ impl<T: Serialize + ToSchema> Response<T> {
             status: StatusCode::OK.as_u16(),
             message: "success".to_string(),
             data: data.into(),
+            meta: response_meta(),
         })
     }
 }
@@ -1,3 +1,12 @@
+pub mod admin;
 pub mod erc20;
+pub mod erc721;
+pub mod health;
+pub mod inventory;
+pub mod liquidity;
 pub mod namiland_erc1155;
 pub mod neco_stake;
+pub mod price;
+pub mod stream;
+pub mod transaction;
+pub mod webhook;
@@ -0,0 +1,97 @@
+use std::env;
+
+use axum::{extract::Query, http::HeaderMap, http::StatusCode, Json};
+use ethers::types::U256;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    apis::{request::request_model::PurgeMetadataCacheRequest, response::response_model::Response},
+    common::{address::parse_address, defines::NetworkType},
+    models::CachePurgeResult,
+    services::{
+        namiland_erc1155::NamiLandERC1155Service, nft_metadata::invalidate_cached_metadata,
+    },
+};
+
+// admin apis require the `x-admin-token` header to match the `ADMIN_TOKEN` env.
+pub fn is_admin(headers: &HeaderMap) -> bool {
+    let admin_token = match env::var("ADMIN_TOKEN") {
+        Ok(admin_token) if !admin_token.is_empty() => admin_token,
+        _ => return false,
+    };
+    match headers.get("x-admin-token") {
+        Some(token) => tokens_match(token.as_bytes(), admin_token.as_bytes()),
+        None => false,
+    }
+}
+
+// Compare the digests of the tokens in constant time, so neither the position of the
+// first differing byte nor the length of the admin token leaks through timing.
+fn tokens_match(token: &[u8], admin_token: &[u8]) -> bool {
+    Sha256::digest(token)
+        .iter()
+        .zip(Sha256::digest(admin_token).iter())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+// purge cached nft uri and metadata
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/metadata-cache",
+    tag = "Admin",
+    params(
+        PurgeMetadataCacheRequest,
+        ("x-admin-token" = String, Header, description = "Admin token")
+    ),
+    responses(
+        (status = 200, description = "Purge metadata cache successfully", body = CachePurgeResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    )
+)]
+pub async fn purge_metadata_cache(
+    headers: HeaderMap,
+    Query(request): Query<PurgeMetadataCacheRequest>,
+) -> Json<Response<CachePurgeResult>> {
+    if !is_admin(&headers) {
+        return Response::err(StatusCode::UNAUTHORIZED, "admin token is invalid");
+    }
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
+        }
+    };
+    let nft_id = match request.nft_id.as_deref().map(U256::from_dec_str) {
+        None => None,
+        Some(Ok(nft_id)) => Some(nft_id),
+        Some(Err(_)) => {
+            return Response::err(StatusCode::BAD_REQUEST, "nft id is invalid");
+        }
+    };
+
+    let purged = match request.contract_address.as_deref().map(parse_address) {
+        None => NamiLandERC1155Service::invalidate_metadata(network, nft_id).await,
+        Some(Ok(address)) => invalidate_cached_metadata(network, address, nft_id).await,
+        Some(Err(_)) => {
+            return Response::err(StatusCode::BAD_REQUEST, "contract address is invalid");
+        }
+    };
+    Response::ok(CachePurgeResult {
+        purged_entries: purged as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::apis::v1::admin::tokens_match;
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match(b"secret-token", b"secret-token"));
+        assert!(!tokens_match(b"secret-tokem", b"secret-token"));
+        assert!(!tokens_match(b"secret", b"secret-token"));
+        assert!(!tokens_match(b"", b"secret-token"));
+    }
+}
@@ -1,12 +1,20 @@
 use axum::{extract::Query, Json};
-use ethers::types::U256;
 use reqwest::StatusCode;
 
 use crate::{
-    apis::{request::request_model::GetERC20BalanceRequest, response::response_model::Response},
-    common::defines::{NetworkType, SupportedContractType},
-    models::ERC20Token,
-    services::erc20::ERC20Service,
+    apis::{
+        request::request_model::{GetERC20BalanceBatchRequest, GetERC20BalanceRequest},
+        response::response_model::Response,
+    },
+    common::{
+        address::checksum_address,
+        defines::{NetworkType, SupportedContractType},
+        finality::resolve_block_number,
+        name_resolver::{resolve_address, resolve_public_addresses},
+        request_meta::record_network,
+    },
+    models::{ERC20BalanceBatch, ERC20Token},
+    services::{erc20::ERC20Service, multi_address, price},
 };
 
 #[utoipa::path(
@@ -31,6 +39,7 @@ pub async fn get_erc20_balance(
             return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
         }
     };
+    record_network(network);
     let contract_type = match request.contract_type.as_str() {
         "neco" => SupportedContractType::NAMIX,
         "nfish" => SupportedContractType::FISHX,
@@ -39,22 +48,97 @@ pub async fn get_erc20_balance(
         }
     };
 
-    let symbol = ERC20Service::new(contract_type, network)
+    let public_address = match resolve_address(network, &request.public_address).await {
+        Ok(address) => address,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+    let block_number = match resolve_block_number(network, request.finality.as_deref()).await {
+        Ok(block_number) => block_number,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let erc20_service = match ERC20Service::new(contract_type, network) {
+        Ok(erc20_service) => erc20_service.at_block(Some(block_number)),
+        Err(e) => {
+            return Response::err(StatusCode::BAD_REQUEST, e.to_string().as_str());
+        }
+    };
+
+    let symbol = erc20_service
         .get_symbol()
         .await
         .unwrap_or_else(|_| "unknown".to_string());
-    let amount = ERC20Service::new(contract_type.clone(), network)
-        .get_balance(&request.public_address)
+    let amount = match erc20_service
+        .get_balance(&checksum_address(&public_address))
         .await
-        .unwrap_or_else(|_| U256::zero());
-    let decimal = ERC20Service::new(contract_type.clone(), network)
-        .get_decimal()
-        .await
-        .unwrap_or_else(|_| 0);
+    {
+        Ok(amount) => amount,
+        // a read pinned to a block failing, e.g. on a pruned node, is not reported as 0.
+        Err(err) => return Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    };
+    let decimal = erc20_service.get_decimal().await.unwrap_or_else(|_| 0);
 
-    Response::ok(ERC20Token {
+    let mut token = ERC20Token {
         symbol,
         decimal,
         amount: amount.to_string(),
-    })
+        block_number: Some(block_number),
+        ..Default::default()
+    };
+    if request.include_usd.unwrap_or(false) {
+        price::value_token(network, erc20_service.address(), &mut token).await;
+    }
+    Response::ok(token)
+}
+
+// get erc20 balances of many addresses and the total across them
+#[utoipa::path(
+    post,
+    path = "/v1/erc20/balance/batch",
+    tag = "ERC20",
+    request_body = GetERC20BalanceBatchRequest,
+    responses(
+        (status = 200, description = "Get ERC20 token balances successfully", body = ERC20BalanceBatchResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_erc20_balance_batch(
+    Json(request): Json<GetERC20BalanceBatchRequest>,
+) -> Json<Response<ERC20BalanceBatch>> {
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
+        }
+    };
+    record_network(network);
+    let contract_type = match request.contract_type.as_str() {
+        "neco" => SupportedContractType::NAMIX,
+        "nfish" => SupportedContractType::FISHX,
+        _ => {
+            return Response::err(StatusCode::BAD_REQUEST, "contract type error");
+        }
+    };
+    let public_addresses = match resolve_public_addresses(network, &request.public_addresses).await
+    {
+        Ok(public_addresses) => public_addresses,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+    let block_number = match resolve_block_number(network, request.finality.as_deref()).await {
+        Ok(block_number) => block_number,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let erc20_service = match ERC20Service::new(contract_type, network) {
+        Ok(erc20_service) => erc20_service.at_block(Some(block_number)),
+        Err(e) => {
+            return Response::err(StatusCode::BAD_REQUEST, e.to_string().as_str());
+        }
+    };
+    let mut batch =
+        multi_address::get_erc20_balance_batch(&erc20_service, network, &public_addresses).await;
+    if request.include_usd.unwrap_or(false) {
+        price::value_balance_batch(network, erc20_service.address(), &mut batch).await;
+    }
+    Response::ok(batch)
 }
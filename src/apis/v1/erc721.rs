@@ -0,0 +1,159 @@
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    Json,
+};
+use ethers::types::U256;
+
+use crate::{
+    apis::{
+        request::request_model::{GetERC721OwnershipRequest, GetERC721TokenRequest},
+        response::response_model::Response,
+    },
+    common::{
+        address::{lookup_address_details, parse_address},
+        config::config,
+        defines::NetworkType,
+        finality::resolve_block_number,
+        name_resolver::resolve_address,
+        request_meta::record_network,
+    },
+    models::{ERC721Ownership, ERC721TokenOwner, NamiLandERC1155NFTMetadata},
+    services::erc721::ERC721Service,
+};
+
+// get tokens of an ERC721 collection owned by public address
+#[utoipa::path(
+    get,
+    path = "/v1/erc721/ownership",
+    tag = "ERC721",
+    params(
+        GetERC721OwnershipRequest
+    ),
+    responses(
+        (status = 200, description = "Get ERC721 ownership successfully", body = ERC721OwnershipResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_erc721_ownership(
+    Query(request): Query<GetERC721OwnershipRequest>,
+) -> Json<Response<ERC721Ownership>> {
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
+        }
+    };
+    record_network(network);
+    let contract_address = match parse_address(&request.contract_address) {
+        Ok(address) => address,
+        Err(_) => {
+            return Response::err(StatusCode::BAD_REQUEST, "contract address is invalid");
+        }
+    };
+    let public_address = match resolve_address(network, &request.public_address).await {
+        Ok(address) => address,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let block_number = match resolve_block_number(network, None).await {
+        Ok(block_number) => block_number,
+        Err(err) => return Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    };
+
+    let erc721_service = match ERC721Service::new(network, contract_address) {
+        Ok(erc721_service) => erc721_service.at_block(Some(block_number)),
+        Err(e) => {
+            return Response::err(StatusCode::BAD_REQUEST, e.to_string().as_str());
+        }
+    };
+    // transfers are scanned from the deployment block of a configured collection,
+    // `from_block` only narrows the scan.
+    let from_block = config()
+        .find_erc721_collection(network, contract_address)
+        .and_then(|collection| collection.start_block)
+        .map(|start_block| request.from_block.unwrap_or_default().max(start_block));
+    let (ownership, (primary_name, is_contract)) = tokio::join!(
+        erc721_service.get_ownership(public_address, from_block),
+        lookup_address_details(network, public_address)
+    );
+
+    match ownership {
+        Ok(mut ownership) => {
+            ownership.primary_name = primary_name;
+            ownership.is_contract = is_contract;
+            Response::ok(ownership)
+        }
+        Err(err) => Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    }
+}
+
+// get metadata of an ERC721 token
+#[utoipa::path(
+    get,
+    path = "/v1/erc721/metadata/{chain_id}/{contract_address}/{token_id}",
+    tag = "ERC721",
+    params(
+        GetERC721TokenRequest
+    ),
+    responses(
+        (status = 200, description = "Get ERC721 metadata successfully", body = ERC721MetadataResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_erc721_metadata(
+    Path(param): Path<GetERC721TokenRequest>,
+) -> Json<Response<NamiLandERC1155NFTMetadata>> {
+    let (erc721_service, token_id) = match parse_token_request(&param) {
+        Ok(parsed) => parsed,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err),
+    };
+
+    match erc721_service.get_metadata(&token_id).await {
+        Ok(metadata) => Response::ok(metadata),
+        Err(e) => Response::err(StatusCode::BAD_REQUEST, &e.to_string()),
+    }
+}
+
+// get owner of an ERC721 token
+#[utoipa::path(
+    get,
+    path = "/v1/erc721/owner/{chain_id}/{contract_address}/{token_id}",
+    tag = "ERC721",
+    params(
+        GetERC721TokenRequest
+    ),
+    responses(
+        (status = 200, description = "Get ERC721 token owner successfully", body = ERC721OwnerResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_erc721_owner(
+    Path(param): Path<GetERC721TokenRequest>,
+) -> Json<Response<ERC721TokenOwner>> {
+    let (erc721_service, token_id) = match parse_token_request(&param) {
+        Ok(parsed) => parsed,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err),
+    };
+
+    let erc721_service = match resolve_block_number(erc721_service.network, None).await {
+        Ok(block_number) => erc721_service.at_block(Some(block_number)),
+        Err(err) => return Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    };
+    match erc721_service.get_owner(&token_id).await {
+        Ok(owner) => Response::ok(owner),
+        Err(e) => Response::err(StatusCode::BAD_REQUEST, &e.to_string()),
+    }
+}
+
+fn parse_token_request(param: &GetERC721TokenRequest) -> Result<(ERC721Service, U256), String> {
+    let network =
+        NetworkType::from_chain_id(param.chain_id.into()).ok_or("chain id is not supported")?;
+    record_network(network);
+    let contract_address =
+        parse_address(&param.contract_address).map_err(|_| "contract address is invalid")?;
+    let token_id = U256::from_dec_str(&param.token_id).map_err(|_| "token id is invalid")?;
+    let erc721_service =
+        ERC721Service::new(network, contract_address).map_err(|err| err.to_string())?;
+    Ok((erc721_service, token_id))
+}
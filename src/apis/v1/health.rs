@@ -0,0 +1,34 @@
+use axum::Json;
+
+use crate::{
+    apis::response::response_model::Response, models::HealthStatus,
+    workers::metadata_refresher::get_refresh_status,
+};
+
+// get health of the service and status of the background metadata refresh
+#[utoipa::path(
+    get,
+    path = "/v1/health",
+    tag = "Health",
+    responses(
+        (status = 200, description = "Get health status successfully", body = HealthResponse),
+    )
+)]
+pub async fn get_health() -> Json<Response<HealthStatus>> {
+    let metadata_refresh = get_refresh_status().await;
+    let status = if metadata_refresh
+        .iter()
+        .any(|status| status.last_finished_at.is_none())
+    {
+        "starting"
+    } else if metadata_refresh.iter().any(|status| status.failed > 0) {
+        "degraded"
+    } else {
+        "ok"
+    };
+
+    Response::ok(HealthStatus {
+        status: status.to_string(),
+        metadata_refresh,
+    })
+}
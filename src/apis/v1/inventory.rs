@@ -0,0 +1,43 @@
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    Json,
+};
+
+use crate::{
+    apis::{
+        request::request_model::{GetWalletInventoryRequest, UsdValueOption},
+        response::response_model::Response,
+    },
+    common::{defines::NetworkType, name_resolver::resolve_address},
+    models::WalletInventory,
+    services::inventory::get_wallet_inventory,
+};
+
+// get native and erc20 balances, nfts and stake info of an address on every network
+#[utoipa::path(
+    get,
+    path = "/v1/wallet/{public_address}/inventory",
+    tag = "Wallet",
+    params(
+        GetWalletInventoryRequest,
+        UsdValueOption
+    ),
+    responses(
+        (status = 200, description = "Get wallet inventory successfully", body = WalletInventoryResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_inventory(
+    Path(param): Path<GetWalletInventoryRequest>,
+    Query(option): Query<UsdValueOption>,
+) -> Json<Response<WalletInventory>> {
+    // `.eth` names are resolved on ethereum mainnet and `.bnb` names on bsc mainnet.
+    let public_address =
+        match resolve_address(NetworkType::EthereumMainnet, &param.public_address).await {
+            Ok(address) => address,
+            Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+        };
+
+    Response::ok(get_wallet_inventory(public_address, option.include_usd.unwrap_or(false)).await)
+}
@@ -0,0 +1,61 @@
+use axum::{extract::Query, http::StatusCode, Json};
+
+use crate::{
+    apis::{request::request_model::GetLPPositionRequest, response::response_model::Response},
+    common::{
+        config::config, defines::NetworkType, finality::resolve_block_number,
+        name_resolver::resolve_address, request_meta::record_network,
+    },
+    models::LPPosition,
+    services::liquidity,
+};
+
+// get lp token balance, share of pool and underlying token amounts of an address
+#[utoipa::path(
+    get,
+    path = "/v1/lp/position",
+    tag = "Liquidity",
+    params(
+        GetLPPositionRequest
+    ),
+    responses(
+        (status = 200, description = "Get LP position successfully", body = LPPositionResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_lp_position(
+    Query(request): Query<GetLPPositionRequest>,
+) -> Json<Response<LPPosition>> {
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
+        }
+    };
+    record_network(network);
+    let pair = match config().find_liquidity_pair(network, &request.pair) {
+        Some(pair) => pair,
+        None => return Response::err(StatusCode::BAD_REQUEST, "pair is not found"),
+    };
+    let public_address = match resolve_address(network, &request.public_address).await {
+        Ok(address) => address,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let block_number = match resolve_block_number(network, None).await {
+        Ok(block_number) => block_number,
+        Err(err) => return Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    };
+
+    let position = liquidity::get_lp_position(
+        pair,
+        public_address,
+        request.include_usd.unwrap_or(false),
+        Some(block_number),
+    )
+    .await;
+    match position {
+        Ok(position) => Response::ok(position),
+        Err(err) => Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    }
+}
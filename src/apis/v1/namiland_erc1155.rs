@@ -1,6 +1,7 @@
 use axum::{
     extract::{Path, Query},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response as HttpResponse},
     Json,
 };
 use ethers::types::U256;
@@ -8,12 +9,35 @@ use std::borrow::Borrow;
 
 use crate::{
     apis::{
-        request::request_model::{GetERC1155NFTMetadataRequest, GetNFTOwnershipRequest},
+        request::request_model::{
+            GetERC1155NFTMetadataRequest, GetLockedNFTsRequest, GetNFTCatalogueRequest,
+            GetNFTCollectionRequest, GetNFTFacetsRequest, GetNFTHoldersRequest, GetNFTImageRequest,
+            GetNFTMetadataBatchRequest, GetNFTOwnershipBatchRequest, GetNFTOwnershipRequest,
+            GetNFTPermissionRequest, ImageProxyOption,
+        },
         response::response_model::Response,
     },
-    common::defines::{GameClient, NetworkType},
-    models::{NamiLandERC1155NFTMetadata, NamiLandNFTOwnership},
-    services::namiland_erc1155::NamiLandERC1155Service,
+    common::{
+        address::lookup_address_details,
+        config::config,
+        defines::{NetworkType, IMAGE_CACHE_CONTROL, NFT_METADATA_BATCH_MAX_SIZE},
+        finality::resolve_block_number,
+        name_resolver::{resolve_address, resolve_public_addresses},
+        pagination::{page_params, SortOrder},
+        request_meta::{record_block, record_network},
+    },
+    models::{
+        ContractURIMetadata, EmptyData, NFTMetadataFailure, NFTMetadataItem,
+        NamiLandERC1155NFTMetadata, NamiLandLockedNFTs, NamiLandNFTCatalogue,
+        NamiLandNFTCollection, NamiLandNFTFacets, NamiLandNFTHolders, NamiLandNFTMetadataBatch,
+        NamiLandNFTOwnership, NamiLandNFTOwnershipBatch, NamiLandNFTPermission,
+    },
+    services::{
+        catalogue::{CatalogueFilter, CatalogueService, CatalogueSortField},
+        image_proxy::{proxied_image_url, ImageProxyService, ThumbnailFormat},
+        multi_address,
+        namiland_erc1155::{HolderSortField, NamiLandERC1155Service},
+    },
 };
 
 // get nft metadata by nft id
@@ -22,7 +46,8 @@ use crate::{
     path = "/v1/namiland-game-item-nft/metadata/{chain_id}/{nft_id}",
     tag = "ERC1155",
     params(
-        GetERC1155NFTMetadataRequest
+        GetERC1155NFTMetadataRequest,
+        ImageProxyOption
     ),
     responses(
         (status = 200, description = "Get NFT metadata successfully", body = ERC1155OwnershipResponse),
@@ -31,6 +56,7 @@ use crate::{
 )]
 pub async fn get_nft_metadata(
     Path(param): Path<GetERC1155NFTMetadataRequest>,
+    Query(option): Query<ImageProxyOption>,
 ) -> Json<Response<NamiLandERC1155NFTMetadata>> {
     let network = match param.chain_id {
         1 => NetworkType::EthereumMainnet,
@@ -39,6 +65,7 @@ pub async fn get_nft_metadata(
             return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
         }
     };
+    record_network(network);
     let nft_id = match U256::from_dec_str(param.nft_id.borrow()) {
         Ok(nft_id) => nft_id,
         Err(_) => {
@@ -54,7 +81,12 @@ pub async fn get_nft_metadata(
     };
     let ownership = erc1155_service.get_metadata_by_nft_id(&nft_id).await;
     match ownership {
-        Ok(metadata) => Response::ok(metadata),
+        Ok(mut metadata) => {
+            if option.proxy_image.unwrap_or(false) && !metadata.image.is_empty() {
+                metadata.image = proxied_image_url(param.chain_id, &param.nft_id);
+            }
+            Response::ok(metadata)
+        }
         Err(e) => Response::err(StatusCode::BAD_REQUEST, &e.to_string()),
     }
 }
@@ -75,23 +107,272 @@ pub async fn get_nft_metadata(
 pub async fn get_nft_ownership(
     Query(request): Query<GetNFTOwnershipRequest>,
 ) -> Json<Response<NamiLandNFTOwnership>> {
-    let game_client = match request.game_client {
-        0 => GameClient::NamiLand,
-        _ => return Response::err(StatusCode::BAD_REQUEST, "game client type error"),
-    };
-    let network = match request.chain_id {
-        1 => NetworkType::EthereumMainnet,
-        5 => NetworkType::GoerliTestnet,
-        _ => {
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
             return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
         }
     };
-    let pubic_address = match request.public_address.parse::<ethers::types::Address>() {
+    record_network(network);
+    let collection = match config().select_collection(
+        network,
+        request.collection.as_deref(),
+        request.game_client,
+    ) {
+        Some(collection) => collection.clone(),
+        None => return Response::err(StatusCode::BAD_REQUEST, "collection is not found"),
+    };
+    let pubic_address = match resolve_address(network, &request.public_address).await {
         Ok(address) => address,
-        Err(_) => {
-            return Response::err(StatusCode::BAD_REQUEST, "public address is invalid");
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+    let filter = match CatalogueFilter::parse(
+        request.traits.as_deref(),
+        request.nft_type1.as_deref(),
+        request.nft_type2.as_deref(),
+        request.name.as_deref(),
+    ) {
+        Ok(filter) => filter,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    // images are proxied for the default collection only.
+    let proxy_image = request.proxy_image.unwrap_or(false)
+        && config()
+            .default_collection(network)
+            .map(|default| default.address)
+            == Some(collection.address);
+
+    let block_number = match resolve_block_number(network, request.finality.as_deref()).await {
+        Ok(block_number) => block_number,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let erc1155_service = match NamiLandERC1155Service::from_collection(collection) {
+        Ok(erc1155_service) => erc1155_service.at_block(Some(block_number)),
+        Err(e) => {
+            return Response::err(StatusCode::BAD_REQUEST, e.to_string().as_str());
         }
     };
+    let (ownership, (primary_name, is_contract)) = tokio::join!(
+        erc1155_service.get_nft_ownership(pubic_address),
+        lookup_address_details(network, pubic_address)
+    );
+
+    match ownership {
+        Ok(mut ownership) => {
+            ownership.primary_name = primary_name;
+            ownership.is_contract = is_contract;
+            ownership
+                .ownerships
+                .retain(|item| filter.matches(&item.nft_metadata));
+            if proxy_image {
+                ownership
+                    .ownerships
+                    .iter_mut()
+                    .filter(|item| !item.nft_metadata.image.is_empty())
+                    .for_each(|item| {
+                        item.nft_metadata.image = proxied_image_url(request.chain_id, &item.nft_id)
+                    });
+            }
+            Response::ok(ownership)
+        }
+        Err(err) => Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    }
+}
+
+// get nft ownership of many addresses and the amount of each nft id summed across them
+#[utoipa::path(
+    post,
+    path = "/v1/namiland-game-item-nft/ownership/batch",
+    tag = "ERC1155",
+    request_body = GetNFTOwnershipBatchRequest,
+    responses(
+        (status = 200, description = "Get NFT ownership successfully", body = ERC1155OwnershipBatchResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_nft_ownership_batch(
+    Json(request): Json<GetNFTOwnershipBatchRequest>,
+) -> Json<Response<NamiLandNFTOwnershipBatch>> {
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
+        }
+    };
+    record_network(network);
+    let collection = match config().select_collection(
+        network,
+        request.collection.as_deref(),
+        request.game_client,
+    ) {
+        Some(collection) => collection.clone(),
+        None => return Response::err(StatusCode::BAD_REQUEST, "collection is not found"),
+    };
+    let public_addresses = match resolve_public_addresses(network, &request.public_addresses).await
+    {
+        Ok(public_addresses) => public_addresses,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let block_number = match resolve_block_number(network, request.finality.as_deref()).await {
+        Ok(block_number) => block_number,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let erc1155_service = match NamiLandERC1155Service::from_collection(collection) {
+        Ok(erc1155_service) => erc1155_service.at_block(Some(block_number)),
+        Err(e) => {
+            return Response::err(StatusCode::BAD_REQUEST, e.to_string().as_str());
+        }
+    };
+    Response::ok(multi_address::get_nft_ownership_batch(&erc1155_service, &public_addresses).await)
+}
+
+// list all game items, filtered by traits, types and name
+#[utoipa::path(
+    get,
+    path = "/v1/namiland-game-item-nft/catalogue",
+    tag = "ERC1155",
+    params(
+        GetNFTCatalogueRequest
+    ),
+    responses(
+        (status = 200, description = "Get NFT catalogue successfully", body = ERC1155CatalogueResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_nft_catalogue(
+    Query(request): Query<GetNFTCatalogueRequest>,
+) -> Json<Response<NamiLandNFTCatalogue>> {
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
+        }
+    };
+    record_network(network);
+    let filter = match CatalogueFilter::parse(
+        request.traits.as_deref(),
+        request.nft_type1.as_deref(),
+        request.nft_type2.as_deref(),
+        request.name.as_deref(),
+    ) {
+        Ok(filter) => filter,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+    let sort_by = match CatalogueSortField::parse(request.sort_by.as_deref()) {
+        Some(sort_by) => sort_by,
+        None => return Response::err(StatusCode::BAD_REQUEST, "sort field is invalid"),
+    };
+    let order = match SortOrder::parse(request.order.as_deref()) {
+        Some(order) => order,
+        None => return Response::err(StatusCode::BAD_REQUEST, "sort order is invalid"),
+    };
+    let (page, page_size) = page_params(request.page, request.page_size);
+
+    let catalogue_service = match CatalogueService::new(network) {
+        Ok(catalogue_service) => catalogue_service,
+        Err(e) => {
+            return Response::err(StatusCode::BAD_REQUEST, e.to_string().as_str());
+        }
+    };
+    let catalogue = catalogue_service
+        .get_catalogue(&filter, sort_by, order, page, page_size)
+        .await;
+
+    match catalogue {
+        Ok(catalogue) => Response::ok(catalogue),
+        Err(err) => Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    }
+}
+
+// count game items per type and trait value
+#[utoipa::path(
+    get,
+    path = "/v1/namiland-game-item-nft/catalogue/facets",
+    tag = "ERC1155",
+    params(
+        GetNFTFacetsRequest
+    ),
+    responses(
+        (status = 200, description = "Get NFT trait facets successfully", body = ERC1155FacetsResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_nft_facets(
+    Query(request): Query<GetNFTFacetsRequest>,
+) -> Json<Response<NamiLandNFTFacets>> {
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
+        }
+    };
+    record_network(network);
+    let filter = match CatalogueFilter::parse(
+        request.traits.as_deref(),
+        request.nft_type1.as_deref(),
+        request.nft_type2.as_deref(),
+        request.name.as_deref(),
+    ) {
+        Ok(filter) => filter,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+
+    let catalogue_service = match CatalogueService::new(network) {
+        Ok(catalogue_service) => catalogue_service,
+        Err(e) => {
+            return Response::err(StatusCode::BAD_REQUEST, e.to_string().as_str());
+        }
+    };
+
+    match catalogue_service.get_facets(&filter).await {
+        Ok(facets) => Response::ok(facets),
+        Err(err) => Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    }
+}
+
+// get holders of a nft id or of the whole collection
+#[utoipa::path(
+    get,
+    path = "/v1/namiland-game-item-nft/holders",
+    tag = "ERC1155",
+    params(
+        GetNFTHoldersRequest
+    ),
+    responses(
+        (status = 200, description = "Get NFT holders successfully", body = ERC1155HoldersResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_nft_holders(
+    Query(request): Query<GetNFTHoldersRequest>,
+) -> Json<Response<NamiLandNFTHolders>> {
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
+        }
+    };
+    record_network(network);
+    let nft_id = match request.nft_id.as_deref().map(U256::from_dec_str) {
+        None => None,
+        Some(Ok(nft_id)) => Some(nft_id),
+        Some(Err(_)) => {
+            return Response::err(StatusCode::BAD_REQUEST, "nft id is invalid");
+        }
+    };
+    let sort_by = match HolderSortField::parse(request.sort_by.as_deref()) {
+        Some(sort_by) => sort_by,
+        None => return Response::err(StatusCode::BAD_REQUEST, "sort field is invalid"),
+    };
+    let order = match SortOrder::parse(request.order.as_deref()) {
+        Some(order) => order,
+        None => return Response::err(StatusCode::BAD_REQUEST, "sort order is invalid"),
+    };
+    let (page, page_size) = page_params(request.page, request.page_size);
 
     let erc1155_service = match NamiLandERC1155Service::new(network) {
         Ok(erc1155_service) => erc1155_service,
@@ -99,12 +380,341 @@ pub async fn get_nft_ownership(
             return Response::err(StatusCode::BAD_REQUEST, e.to_string().as_str());
         }
     };
-    let ownership = erc1155_service
-        .get_nft_ownership(pubic_address, game_client, network)
+    let holders = erc1155_service
+        .get_nft_holders(network, nft_id, sort_by, order, page, page_size)
         .await;
 
-    match ownership {
-        Ok(ownership) => Response::ok(ownership),
+    match holders {
+        Ok(holders) => {
+            // holders are read from transfers up to the scanned block.
+            record_block(holders.scanned_block, None);
+            Response::ok(holders)
+        }
         Err(err) => Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
     }
 }
+
+// get all locked nft ids, which can not be transferred or listed
+#[utoipa::path(
+    get,
+    path = "/v1/namiland-game-item-nft/locked",
+    tag = "ERC1155",
+    params(
+        GetLockedNFTsRequest
+    ),
+    responses(
+        (status = 200, description = "Get locked NFT ids successfully", body = ERC1155LockedResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_locked_nfts(
+    Query(request): Query<GetLockedNFTsRequest>,
+) -> Json<Response<NamiLandLockedNFTs>> {
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
+        }
+    };
+    record_network(network);
+    let block_number = match resolve_block_number(network, None).await {
+        Ok(block_number) => block_number,
+        Err(err) => return Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    };
+
+    let erc1155_service = match NamiLandERC1155Service::new(network) {
+        Ok(erc1155_service) => erc1155_service.at_block(Some(block_number)),
+        Err(e) => {
+            return Response::err(StatusCode::BAD_REQUEST, e.to_string().as_str());
+        }
+    };
+
+    match erc1155_service.get_locked_nfts(network).await {
+        Ok(locked_nfts) => Response::ok(locked_nfts),
+        Err(err) => Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    }
+}
+
+// get transfer whitelist, minter and operator approval status of an address
+#[utoipa::path(
+    get,
+    path = "/v1/namiland-game-item-nft/permission",
+    tag = "ERC1155",
+    params(
+        GetNFTPermissionRequest
+    ),
+    responses(
+        (status = 200, description = "Get NFT permission successfully", body = ERC1155PermissionResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_nft_permission(
+    Query(request): Query<GetNFTPermissionRequest>,
+) -> Json<Response<NamiLandNFTPermission>> {
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
+        }
+    };
+    record_network(network);
+    let public_address = match resolve_address(network, &request.public_address).await {
+        Ok(address) => address,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+    let operator = match request.operator.as_deref() {
+        None => None,
+        Some(operator) => match resolve_address(network, operator).await {
+            Ok(operator) => Some(operator),
+            Err(_) => {
+                return Response::err(StatusCode::BAD_REQUEST, "operator address is invalid");
+            }
+        },
+    };
+
+    let block_number = match resolve_block_number(network, None).await {
+        Ok(block_number) => block_number,
+        Err(err) => return Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    };
+
+    let erc1155_service = match NamiLandERC1155Service::new(network) {
+        Ok(erc1155_service) => erc1155_service.at_block(Some(block_number)),
+        Err(e) => {
+            return Response::err(StatusCode::BAD_REQUEST, e.to_string().as_str());
+        }
+    };
+    let (permission, (primary_name, is_contract)) = tokio::join!(
+        erc1155_service.get_nft_permission(public_address, operator, network),
+        lookup_address_details(network, public_address)
+    );
+
+    match permission {
+        Ok(mut permission) => {
+            permission.primary_name = primary_name;
+            permission.is_contract = is_contract;
+            Response::ok(permission)
+        }
+        Err(err) => Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    }
+}
+
+// get contract level info and supply of every token id
+#[utoipa::path(
+    get,
+    path = "/v1/namiland-game-item-nft/collection",
+    tag = "ERC1155",
+    params(
+        GetNFTCollectionRequest
+    ),
+    responses(
+        (status = 200, description = "Get NFT collection successfully", body = ERC1155CollectionResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_nft_collection(
+    Query(request): Query<GetNFTCollectionRequest>,
+) -> Json<Response<NamiLandNFTCollection>> {
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
+        }
+    };
+    record_network(network);
+    let block_number = match resolve_block_number(network, None).await {
+        Ok(block_number) => block_number,
+        Err(err) => return Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    };
+
+    let erc1155_service = match NamiLandERC1155Service::new(network) {
+        Ok(erc1155_service) => erc1155_service.at_block(Some(block_number)),
+        Err(e) => {
+            return Response::err(StatusCode::BAD_REQUEST, e.to_string().as_str());
+        }
+    };
+
+    match erc1155_service.get_collection(network).await {
+        Ok(collection) => Response::ok(collection),
+        Err(err) => Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    }
+}
+
+// get collection metadata for marketplaces, in the shape of OpenSea `contractURI`
+#[utoipa::path(
+    get,
+    path = "/v1/namiland-game-item-nft/contract-uri/{chain_id}",
+    tag = "ERC1155",
+    params(
+        GetNFTCollectionRequest
+    ),
+    responses(
+        (status = 200, description = "Get contract URI metadata successfully", body = ContractURIMetadata),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_contract_uri(
+    Path(param): Path<GetNFTCollectionRequest>,
+) -> Result<Json<ContractURIMetadata>, (StatusCode, Json<Response<EmptyData>>)> {
+    let network = match NetworkType::from_chain_id(param.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Response::err(StatusCode::BAD_REQUEST, "chain id is not supported"),
+            ));
+        }
+    };
+    record_network(network);
+
+    let erc1155_service = match NamiLandERC1155Service::new(network) {
+        Ok(erc1155_service) => erc1155_service,
+        Err(e) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Response::err(StatusCode::BAD_REQUEST, e.to_string().as_str()),
+            ));
+        }
+    };
+
+    match erc1155_service.get_contract_uri().await {
+        Ok(metadata) => Ok(Json(metadata)),
+        Err(err) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+        )),
+    }
+}
+
+// get nft image or its thumbnail, cached on local disk
+#[utoipa::path(
+    get,
+    path = "/v1/namiland-game-item-nft/image/{chain_id}/{nft_id}",
+    tag = "ERC1155",
+    params(
+        GetERC1155NFTMetadataRequest,
+        GetNFTImageRequest
+    ),
+    responses(
+        (status = 200, description = "Get NFT image successfully", content_type = "image/webp"),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_nft_image(
+    Path(param): Path<GetERC1155NFTMetadataRequest>,
+    Query(request): Query<GetNFTImageRequest>,
+) -> HttpResponse {
+    let bad_request = |message: &str| {
+        (
+            StatusCode::BAD_REQUEST,
+            Response::<EmptyData>::err(StatusCode::BAD_REQUEST, message),
+        )
+            .into_response()
+    };
+    let network = match NetworkType::from_chain_id(param.chain_id.into()) {
+        Some(network) => network,
+        None => return bad_request("chain id is not supported"),
+    };
+    let nft_id = match U256::from_dec_str(&param.nft_id) {
+        Ok(nft_id) => nft_id,
+        Err(_) => return bad_request("nft id is invalid"),
+    };
+    let format = match ThumbnailFormat::parse(request.format.as_deref()) {
+        Some(format) => format,
+        None => return bad_request("image format is not supported"),
+    };
+
+    let image_proxy_service = match ImageProxyService::new(network) {
+        Ok(image_proxy_service) => image_proxy_service,
+        Err(e) => return bad_request(e.to_string().as_str()),
+    };
+    match image_proxy_service
+        .get_image(&nft_id, request.size, format)
+        .await
+    {
+        Ok(image) => (
+            [
+                (header::CONTENT_TYPE, format.content_type()),
+                (header::CACHE_CONTROL, IMAGE_CACHE_CONTROL),
+            ],
+            image,
+        )
+            .into_response(),
+        Err(e) => bad_request(e.to_string().as_str()),
+    }
+}
+
+// get metadata of many nft ids in one request
+#[utoipa::path(
+    post,
+    path = "/v1/namiland-game-item-nft/metadata/batch",
+    tag = "ERC1155",
+    request_body = GetNFTMetadataBatchRequest,
+    responses(
+        (status = 200, description = "Get NFT metadata successfully", body = ERC1155MetadataBatchResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_nft_metadata_batch(
+    Json(request): Json<GetNFTMetadataBatchRequest>,
+) -> Json<Response<NamiLandNFTMetadataBatch>> {
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
+        }
+    };
+    record_network(network);
+    let erc1155_service = match NamiLandERC1155Service::new(network) {
+        Ok(erc1155_service) => erc1155_service,
+        Err(e) => {
+            return Response::err(StatusCode::BAD_REQUEST, e.to_string().as_str());
+        }
+    };
+    let nft_ids: Vec<String> = match request.nft_ids {
+        Some(nft_ids) if !nft_ids.is_empty() => nft_ids,
+        _ => erc1155_service
+            .collection
+            .token_ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect(),
+    };
+    if nft_ids.len() > NFT_METADATA_BATCH_MAX_SIZE {
+        return Response::err(
+            StatusCode::BAD_REQUEST,
+            &format!(
+                "at most {} nft ids are allowed",
+                NFT_METADATA_BATCH_MAX_SIZE
+            ),
+        );
+    }
+
+    let mut batch = NamiLandNFTMetadataBatch {
+        network,
+        ..Default::default()
+    };
+    let mut valid_ids = vec![];
+    for nft_id in nft_ids {
+        match U256::from_dec_str(&nft_id) {
+            Ok(id) => valid_ids.push(id),
+            Err(_) => batch.failures.push(NFTMetadataFailure {
+                nft_id,
+                error: "nft id is invalid".to_string(),
+            }),
+        }
+    }
+
+    for (id, result) in erc1155_service.get_metadata_batch(&valid_ids).await {
+        let nft_id = id.to_string();
+        match result {
+            Ok(mut metadata) => {
+                if request.proxy_image.unwrap_or(false) && !metadata.image.is_empty() {
+                    metadata.image = proxied_image_url(request.chain_id, &nft_id);
+                }
+                batch.items.push(NFTMetadataItem { nft_id, metadata });
+            }
+            Err(error) => batch.failures.push(NFTMetadataFailure { nft_id, error }),
+        }
+    }
+    Response::ok(batch)
+}
@@ -1,11 +1,25 @@
-use axum::{extract::Path, http::StatusCode, Json};
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    Json,
+};
 use ethers::types::U256;
 use log::info;
 
-use crate::apis::request::request_model::GetNECOStakedInfoRequest;
+use crate::apis::request::request_model::{
+    FinalityOption, GetNECOStakedInfoBatchRequest, GetNECOStakedInfoRequest,
+};
 use crate::{
-    apis::response::response_model::Response, common::defines::NetworkType,
-    models::NamiXStakedInfo, services::neco_stake::NecoStakeService,
+    apis::response::response_model::Response,
+    common::{
+        address::{checksum_address, lookup_address_details},
+        defines::NetworkType,
+        finality::resolve_block_number,
+        name_resolver::{resolve_address, resolve_public_addresses},
+        request_meta::record_network,
+    },
+    models::{NamiXStakedInfo, NamiXStakedInfoBatch},
+    services::{multi_address::get_neco_staked_batch, neco_stake::NecoStakeService},
 };
 
 // get neco staked info by public address
@@ -14,7 +28,8 @@ use crate::{
     path = "/v1/neco-staked-info/{network}/{public_address}",
     tag = "NECO",
     params(
-        GetNECOStakedInfoRequest
+        GetNECOStakedInfoRequest,
+        FinalityOption
     ),
     responses(
         (status = 200, description = "Get NECO staked info successfully", body = NECOStakedInfoResponse),
@@ -23,6 +38,7 @@ use crate::{
 )]
 pub async fn get_neco_staked_info(
     Path(param): Path<GetNECOStakedInfoRequest>,
+    Query(option): Query<FinalityOption>,
 ) -> Json<Response<NamiXStakedInfo>> {
     info!(
         "get_neco_staked_info - public_address: {:?}, network: {:?}",
@@ -33,19 +49,72 @@ pub async fn get_neco_staked_info(
         1 => NetworkType::BSCTestNetwork,
         _ => return Response::err(StatusCode::BAD_REQUEST, "network type error"),
     };
+    record_network(network);
+    let public_address = match resolve_address(network, &param.public_address).await {
+        Ok(address) => address,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+    let account = checksum_address(&public_address);
+    let block_number = match resolve_block_number(network, option.finality.as_deref()).await {
+        Ok(block_number) => block_number,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
 
-    // let staked_amount = NecoStakeService::new(network)
-    //     .get_neco_staked_amount(&param.public_address)
-    //     .await
-    //     .unwrap_or_else(|_| U256::from(0));
-    // let staked_time = NecoStakeService::new(network)
-    //     .get_neco_staked_time(&param.public_address)
-    //     .await
-    //     .unwrap_or_else(|_| U256::from(0));
+    // nothing is staked on networks without the stake contract.
+    let (staked_amount, staked_time) =
+        match NecoStakeService::new(network).map(|service| service.at_block(Some(block_number))) {
+            Ok(stake_service) => {
+                let staked_amount = stake_service.get_neco_staked_amount(&account).await;
+                let staked_time = stake_service.get_neco_staked_time(&account).await;
+                match (staked_amount, staked_time) {
+                    (Ok(staked_amount), Ok(staked_time)) => (staked_amount, staked_time),
+                    // a read pinned to a block failing is not reported as nothing staked.
+                    (Err(err), _) | (_, Err(err)) => {
+                        return Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string())
+                    }
+                }
+            }
+            Err(_) => (U256::from(0), U256::from(0)),
+        };
 
+    let (primary_name, is_contract) = lookup_address_details(network, public_address).await;
     Response::ok(NamiXStakedInfo {
-        public_address: param.public_address.clone(),
-        staked_amount: 0.to_string(),
-        staked_time: 0.to_string(),
+        public_address: account,
+        primary_name,
+        is_contract,
+        staked_amount: staked_amount.to_string(),
+        staked_time: staked_time.to_string(),
+        block_number: Some(block_number),
     })
 }
+
+// get neco staked info of many addresses and the total staked amount
+#[utoipa::path(
+    post,
+    path = "/v1/neco-staked-info/batch",
+    tag = "NECO",
+    request_body = GetNECOStakedInfoBatchRequest,
+    responses(
+        (status = 200, description = "Get NECO staked info successfully", body = NECOStakedInfoBatchResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_neco_staked_info_batch(
+    Json(request): Json<GetNECOStakedInfoBatchRequest>,
+) -> Json<Response<NamiXStakedInfoBatch>> {
+    let network = match NetworkType::from_chain_id(request.chain_id) {
+        Some(network) => network,
+        None => return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported"),
+    };
+    record_network(network);
+    let public_addresses = match resolve_public_addresses(network, &request.public_addresses).await
+    {
+        Ok(public_addresses) => public_addresses,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+    let block_number = match resolve_block_number(network, request.finality.as_deref()).await {
+        Ok(block_number) => block_number,
+        Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    };
+    Response::ok(get_neco_staked_batch(network, &public_addresses, Some(block_number)).await)
+}
@@ -0,0 +1,16 @@
+use axum::Json;
+
+use crate::{apis::response::response_model::Response, models::TokenPrices, services::price};
+
+// get usd prices of all tokens with a configured price pair
+#[utoipa::path(
+    get,
+    path = "/v1/prices",
+    tag = "Price",
+    responses(
+        (status = 200, description = "Get token prices successfully", body = TokenPricesResponse),
+    )
+)]
+pub async fn get_prices() -> Json<Response<TokenPrices>> {
+    Response::ok(price::get_prices().await)
+}
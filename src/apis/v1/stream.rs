@@ -0,0 +1,96 @@
+use std::{convert::Infallible, time::Duration};
+
+use axum::{
+    extract::Query,
+    http::{HeaderMap, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
+    Json,
+};
+use futures::{stream, Stream};
+use tokio::sync::mpsc;
+
+use crate::{
+    apis::{request::request_model::GetWalletStreamRequest, response::response_model::Response},
+    common::{
+        defines::{NetworkType, WALLET_STREAM_CHANNEL_SIZE, WALLET_STREAM_KEEPALIVE_SECS},
+        name_resolver::resolve_public_addresses,
+    },
+    models::EmptyData,
+    services::asset_events::{stream_wallet_changes, WalletStreamItem},
+};
+
+// Subscribe to erc20, erc1155 and stake changes of addresses as server-sent events.
+// `asset_change` events carry the deltas, `block` events mark the last scanned block
+// with the event id, so a reconnect with `Last-Event-ID` resumes after it.
+#[utoipa::path(
+    get,
+    path = "/v1/wallet/stream",
+    tag = "Wallet",
+    params(
+        GetWalletStreamRequest
+    ),
+    responses(
+        (status = 200, description = "Stream of asset_change, block and error events", content_type = "text/event-stream", body = WalletAssetChange),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_wallet_stream(
+    headers: HeaderMap,
+    Query(request): Query<GetWalletStreamRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Json<Response<EmptyData>>> {
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Err(Response::err(
+                StatusCode::BAD_REQUEST,
+                "chain id is not supported",
+            ));
+        }
+    };
+    let public_addresses: Vec<String> = request
+        .public_addresses
+        .split(',')
+        .map(|address| address.trim().to_string())
+        .filter(|address| !address.is_empty())
+        .collect();
+    let public_addresses = match resolve_public_addresses(network, &public_addresses).await {
+        Ok(public_addresses) => public_addresses,
+        Err(err) => return Err(Response::err(StatusCode::BAD_REQUEST, &err.to_string())),
+    };
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    let from_block = match last_event_id {
+        Some(block_number) => Some(block_number.saturating_add(1)),
+        None => request.from_block,
+    };
+
+    let (sender, receiver) = mpsc::channel(WALLET_STREAM_CHANNEL_SIZE);
+    tokio::spawn(stream_wallet_changes(
+        network,
+        public_addresses,
+        from_block,
+        sender,
+    ));
+    let events = stream::unfold(receiver, |mut receiver| async move {
+        let item = receiver.recv().await?;
+        Some((Ok(to_event(item)), receiver))
+    });
+    Ok(Sse::new(events)
+        .keep_alive(KeepAlive::new().interval(Duration::from_secs(WALLET_STREAM_KEEPALIVE_SECS))))
+}
+
+fn to_event(item: WalletStreamItem) -> Event {
+    let event = match item {
+        WalletStreamItem::Change(change) => {
+            Event::default().event("asset_change").json_data(change)
+        }
+        WalletStreamItem::Block(block) => Event::default()
+            .event("block")
+            .id(block.block_number.to_string())
+            .json_data(block),
+        WalletStreamItem::Error(message) => Ok(Event::default().event("error").data(message)),
+    };
+    event.unwrap_or_else(|err| Event::default().event("error").data(err.to_string()))
+}
@@ -0,0 +1,50 @@
+use axum::{extract::Query, http::StatusCode, Json};
+use ethers::types::TxHash;
+
+use crate::{
+    apis::{
+        request::request_model::GetTransactionStatusRequest, response::response_model::Response,
+    },
+    common::{defines::NetworkType, request_meta::record_network},
+    models::TransactionStatus,
+    services::transaction,
+};
+
+// get status, confirmations, gas used and decoded events of a transaction
+#[utoipa::path(
+    get,
+    path = "/v1/tx/status",
+    tag = "Transaction",
+    params(
+        GetTransactionStatusRequest
+    ),
+    responses(
+        (status = 200, description = "Get transaction status successfully", body = TransactionStatusResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+    )
+)]
+pub async fn get_transaction_status(
+    Query(request): Query<GetTransactionStatusRequest>,
+) -> Json<Response<TransactionStatus>> {
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
+        }
+    };
+    record_network(network);
+    let tx_hash = request.tx_hash.trim();
+    if tx_hash.len() != 66 || !tx_hash.starts_with("0x") {
+        return Response::err(StatusCode::BAD_REQUEST, "tx hash is invalid");
+    }
+    let tx_hash = match tx_hash.parse::<TxHash>() {
+        Ok(tx_hash) => tx_hash,
+        Err(_) => return Response::err(StatusCode::BAD_REQUEST, "tx hash is invalid"),
+    };
+
+    // receipts can not be read at a block, the meta has no block.
+    match transaction::get_transaction_status(network, tx_hash).await {
+        Ok(status) => Response::ok(status),
+        Err(err) => Response::err(StatusCode::INTERNAL_SERVER_ERROR, &err.to_string()),
+    }
+}
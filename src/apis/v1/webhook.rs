@@ -0,0 +1,191 @@
+use axum::{
+    extract::{Path, Query},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+
+use crate::{
+    apis::{
+        request::request_model::{
+            GetWebhookDeliveriesRequest, RegisterWebhookRequest, WebhookIdParam,
+        },
+        response::response_model::Response,
+        v1::admin::is_admin,
+    },
+    common::{
+        address::{checksum_address, parse_address},
+        defines::{
+            NetworkType, WEBHOOK_DEFAULT_CONFIRMATIONS, WEBHOOK_DELIVERY_LOG_SIZE,
+            WEBHOOK_MAX_CONFIRMATIONS, WEBHOOK_SECRET_MIN_LENGTH,
+        },
+        name_resolver::resolve_public_addresses,
+    },
+    models::{WebhookDeliveries, WebhookSubscription, WebhookSubscriptions},
+    services::webhook,
+};
+
+// register a webhook notified of transfers or stakes of a contract
+#[utoipa::path(
+    post,
+    path = "/v1/admin/webhooks",
+    tag = "Admin",
+    request_body = RegisterWebhookRequest,
+    params(
+        ("x-admin-token" = String, Header, description = "Admin token")
+    ),
+    responses(
+        (status = 200, description = "Register webhook successfully", body = WebhookSubscriptionResponse),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    )
+)]
+pub async fn register_webhook(
+    headers: HeaderMap,
+    Json(request): Json<RegisterWebhookRequest>,
+) -> Json<Response<WebhookSubscription>> {
+    if !is_admin(&headers) {
+        return Response::err(StatusCode::UNAUTHORIZED, "admin token is invalid");
+    }
+    let network = match NetworkType::from_chain_id(request.chain_id.into()) {
+        Some(network) => network,
+        None => {
+            return Response::err(StatusCode::BAD_REQUEST, "chain id is not supported");
+        }
+    };
+    if !request.url.starts_with("http://") && !request.url.starts_with("https://") {
+        return Response::err(StatusCode::BAD_REQUEST, "url is invalid");
+    }
+    if request.secret.len() < WEBHOOK_SECRET_MIN_LENGTH {
+        let message = format!(
+            "secret must have at least {} characters",
+            WEBHOOK_SECRET_MIN_LENGTH
+        );
+        return Response::err(StatusCode::BAD_REQUEST, &message);
+    }
+    let confirmations = request
+        .confirmations
+        .unwrap_or(WEBHOOK_DEFAULT_CONFIRMATIONS);
+    if confirmations > WEBHOOK_MAX_CONFIRMATIONS {
+        let message = format!(
+            "confirmations must not exceed {}",
+            WEBHOOK_MAX_CONFIRMATIONS
+        );
+        return Response::err(StatusCode::BAD_REQUEST, &message);
+    }
+    let contract_address = match parse_address(&request.contract_address) {
+        Ok(address) => address,
+        Err(_) => return Response::err(StatusCode::BAD_REQUEST, "contract address is invalid"),
+    };
+    let public_addresses = request.public_addresses.unwrap_or_default();
+    let public_addresses = match public_addresses.is_empty() {
+        true => vec![],
+        false => match resolve_public_addresses(network, &public_addresses).await {
+            Ok(public_addresses) => public_addresses,
+            Err(err) => return Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+        },
+    };
+
+    let subscription = WebhookSubscription {
+        url: request.url,
+        network,
+        contract_address: checksum_address(&contract_address),
+        event_type: request.event_type,
+        public_addresses: public_addresses.iter().map(checksum_address).collect(),
+        confirmations,
+        ..Default::default()
+    };
+    match webhook::register_subscription(subscription, request.secret).await {
+        Ok(subscription) => Response::ok(subscription),
+        Err(err) => Response::err(StatusCode::BAD_REQUEST, &err.to_string()),
+    }
+}
+
+// list registered webhooks
+#[utoipa::path(
+    get,
+    path = "/v1/admin/webhooks",
+    tag = "Admin",
+    params(
+        ("x-admin-token" = String, Header, description = "Admin token")
+    ),
+    responses(
+        (status = 200, description = "Get webhooks successfully", body = WebhookSubscriptionsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+    )
+)]
+pub async fn get_webhooks(headers: HeaderMap) -> Json<Response<WebhookSubscriptions>> {
+    if !is_admin(&headers) {
+        return Response::err(StatusCode::UNAUTHORIZED, "admin token is invalid");
+    }
+    let subscriptions = webhook::get_subscriptions()
+        .await
+        .into_iter()
+        .map(|stored| stored.subscription)
+        .collect();
+    Response::ok(WebhookSubscriptions { subscriptions })
+}
+
+// delete a webhook and its delivery logs
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/webhooks/{id}",
+    tag = "Admin",
+    params(
+        WebhookIdParam,
+        ("x-admin-token" = String, Header, description = "Admin token")
+    ),
+    responses(
+        (status = 200, description = "Delete webhook successfully", body = WebhookSubscriptionResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Webhook is not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_webhook(
+    headers: HeaderMap,
+    Path(param): Path<WebhookIdParam>,
+) -> Json<Response<WebhookSubscription>> {
+    if !is_admin(&headers) {
+        return Response::err(StatusCode::UNAUTHORIZED, "admin token is invalid");
+    }
+    match webhook::delete_subscription(&param.id).await {
+        Some(subscription) => Response::ok(subscription),
+        None => Response::err(StatusCode::NOT_FOUND, "webhook is not found"),
+    }
+}
+
+// get delivery logs of a webhook, newest first
+#[utoipa::path(
+    get,
+    path = "/v1/admin/webhooks/{id}/deliveries",
+    tag = "Admin",
+    params(
+        WebhookIdParam,
+        GetWebhookDeliveriesRequest,
+        ("x-admin-token" = String, Header, description = "Admin token")
+    ),
+    responses(
+        (status = 200, description = "Get webhook deliveries successfully", body = WebhookDeliveriesResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Webhook is not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_webhook_deliveries(
+    headers: HeaderMap,
+    Path(param): Path<WebhookIdParam>,
+    Query(request): Query<GetWebhookDeliveriesRequest>,
+) -> Json<Response<WebhookDeliveries>> {
+    if !is_admin(&headers) {
+        return Response::err(StatusCode::UNAUTHORIZED, "admin token is invalid");
+    }
+    let limit = request
+        .limit
+        .unwrap_or(WEBHOOK_DELIVERY_LOG_SIZE)
+        .min(WEBHOOK_DELIVERY_LOG_SIZE);
+    match webhook::get_deliveries(&param.id, request.status, limit).await {
+        Some(deliveries) => Response::ok(WebhookDeliveries {
+            subscription_id: param.id,
+            deliveries,
+        }),
+        None => Response::err(StatusCode::NOT_FOUND, "webhook is not found"),
+    }
+}
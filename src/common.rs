@@ -1,3 +1,13 @@
 pub mod address;
+pub mod cache;
+pub mod config;
 pub mod defines;
+pub mod finality;
+pub mod http_cache;
+pub mod multicall;
+pub mod name_resolver;
+pub mod pagination;
 pub mod provider;
+pub mod request_meta;
+pub mod tasks;
+pub mod uri;
@@ -1,6 +1,35 @@
-use super::defines::{Error, NetworkType};
+use std::{env, time::Duration};
+
+use super::{
+    cache::{get_or_load, TtlCache},
+    defines::{
+        Error, NetworkType, CONTRACT_CODE_CACHE_MAX_SIZE, CONTRACT_CODE_CACHE_TTL_SECS,
+        CONTRACT_CODE_NEGATIVE_CACHE_TTL_SECS, MAX_ADDRESSES_PER_REQUEST,
+    },
+    name_resolver::lookup_primary_name,
+    provider::ProviderManager,
+};
 use crate::common::defines::SupportedContractType;
-use ethers_core::types::Address;
+use ethers::{prelude::Lazy, providers::Middleware};
+use ethers_core::{types::Address, utils::to_checksum};
+use tokio::sync::Mutex;
+
+// whether an address has contract code, by network and address.
+static CONTRACT_CODE_CACHES: Lazy<Mutex<TtlCache<(NetworkType, Address), bool>>> =
+    Lazy::new(|| {
+        Mutex::new(TtlCache::new(
+            Duration::from_secs(CONTRACT_CODE_CACHE_TTL_SECS),
+            Duration::from_secs(CONTRACT_CODE_NEGATIVE_CACHE_TTL_SECS),
+            CONTRACT_CODE_CACHE_MAX_SIZE,
+        ))
+    });
+
+// mixed-case addresses with a wrong checksum are rejected when `STRICT_ADDRESS_CHECKSUM` env is true.
+static STRICT_ADDRESS_CHECKSUM: Lazy<bool> = Lazy::new(|| {
+    env::var("STRICT_ADDRESS_CHECKSUM")
+        .map(|value| value == "true" || value == "1")
+        .unwrap_or(false)
+});
 
 // get contract address by contract type and network type
 pub fn get_contract_address(
@@ -22,3 +51,141 @@ pub fn get_contract_address(
 
     Err("network is not supported.".into())
 }
+
+// parse a 0x prefixed hex address, see `parse_address_with`.
+pub fn parse_address(input: &str) -> Result<Address, Error> {
+    parse_address_with(input, *STRICT_ADDRESS_CHECKSUM)
+}
+
+// parse a 0x prefixed address of 40 hex digits. All lowercase or all uppercase addresses
+// carry no checksum, mixed-case ones must match the EIP-55 checksum when `strict_checksum`.
+pub fn parse_address_with(input: &str, strict_checksum: bool) -> Result<Address, Error> {
+    let hex = match input.strip_prefix("0x") {
+        Some(hex) if hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()) => hex,
+        _ => return Err(format!("address is invalid: {}", input).into()),
+    };
+    let address = hex.parse::<Address>()?;
+    let mixed_case =
+        hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    if strict_checksum && mixed_case && checksum_address(&address) != input {
+        return Err(format!("address checksum is invalid: {}", input).into());
+    }
+    Ok(address)
+}
+
+// format an address with EIP-55 checksum.
+pub fn checksum_address(address: &Address) -> String {
+    to_checksum(address, None)
+}
+
+// whether the address is a contract rather than an externally owned account,
+// none when the code could not be read.
+pub async fn is_contract(network: NetworkType, address: Address) -> Option<bool> {
+    get_or_load(&CONTRACT_CODE_CACHES, (network, address), async {
+        let client = match ProviderManager::instance().get_provider(network) {
+            Some(client) => client,
+            None => return Err("get provider failed".into()),
+        };
+        let code = client.get_code(address, None).await?;
+        Ok(!code.as_ref().is_empty())
+    })
+    .await
+    .ok()
+}
+
+// get the primary name of an address and whether it is a contract together.
+pub async fn lookup_address_details(
+    network: NetworkType,
+    address: Address,
+) -> (Option<String>, Option<bool>) {
+    tokio::join!(
+        lookup_primary_name(network, address),
+        is_contract(network, address)
+    )
+}
+
+// check the number of wallet addresses of a multi-address request.
+pub fn check_address_count(count: usize) -> Result<(), Error> {
+    if count == 0 {
+        return Err("public addresses are empty".into());
+    }
+    if count > MAX_ADDRESSES_PER_REQUEST {
+        return Err(format!(
+            "at most {} public addresses are allowed",
+            MAX_ADDRESSES_PER_REQUEST
+        )
+        .into());
+    }
+    Ok(())
+}
+
+// parse wallet addresses of a multi-address request, duplicates are dropped.
+pub fn parse_public_addresses(public_addresses: &[String]) -> Result<Vec<Address>, Error> {
+    check_address_count(public_addresses.len())?;
+    let mut addresses: Vec<Address> = vec![];
+    for public_address in public_addresses {
+        let address = parse_address(public_address)?;
+        if !addresses.contains(&address) {
+            addresses.push(address);
+        }
+    }
+    Ok(addresses)
+}
+
+// get the block number to start scanning contract events from.
+pub fn get_contract_start_block(
+    contract_type: SupportedContractType,
+    network_type: NetworkType,
+) -> Result<u64, Error> {
+    // make sure the contract is deployed on the network.
+    get_contract_address(contract_type, network_type)?;
+
+    // deployment blocks are not recorded yet, scanning from genesis is far too slow,
+    // event scans need `start_block` of the collection in the config.
+    Err("deployment block of the contract is not recorded".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::address::{checksum_address, parse_address_with, parse_public_addresses};
+
+    #[test]
+    fn test_parse_public_addresses() {
+        let addresses = parse_public_addresses(&[
+            "0x5FaB721a3fa13c0219EB24C121f9F6482f64f274".to_string(),
+            "0x5fab721a3fa13c0219eb24c121f9f6482f64f274".to_string(),
+            "0x0000000000000000000000000000000000000001".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(addresses.len(), 2);
+
+        assert!(parse_public_addresses(&[]).is_err());
+        assert!(parse_public_addresses(&["0x1234".to_string()]).is_err());
+        assert!(parse_public_addresses(&vec![
+            "0x0000000000000000000000000000000000000001"
+                .to_string();
+            21
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_address() {
+        let checksummed = "0x5FaB721a3fa13c0219EB24C121f9F6482f64f274";
+        let address = parse_address_with(checksummed, true).unwrap();
+        assert_eq!(checksum_address(&address), checksummed);
+        assert_eq!(
+            parse_address_with(&checksummed.to_lowercase(), true).unwrap(),
+            address
+        );
+
+        // wrong checksum is only rejected in strict mode.
+        let wrong_checksum = "0x5fAB721a3fa13c0219EB24C121f9F6482f64f274";
+        assert!(parse_address_with(wrong_checksum, true).is_err());
+        assert_eq!(parse_address_with(wrong_checksum, false).unwrap(), address);
+
+        assert!(parse_address_with("5FaB721a3fa13c0219EB24C121f9F6482f64f274", false).is_err());
+        assert!(parse_address_with("0x5FaB721a3fa13c0219EB24C121f9F6482f64f27", false).is_err());
+        assert!(parse_address_with("0x5FaB721a3fa13c0219EB24C121f9F6482f64f27g", false).is_err());
+    }
+}
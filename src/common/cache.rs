@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::Mutex;
+
+use super::{defines::Error, request_meta::record_cache_hit};
+
+struct CacheEntry<V> {
+    // failed lookups are cached as well, with a shorter ttl.
+    value: Result<V, String>,
+    inserted_at: Instant,
+    expires_at: Instant,
+    last_accessed: Instant,
+}
+
+// A bounded cache, entries expire after ttl and the least recently used entry
+// is evicted when the cache is full.
+pub struct TtlCache<K, V> {
+    entries: HashMap<K, CacheEntry<V>>,
+    ttl: Duration,
+    negative_ttl: Duration,
+    max_size: usize,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> TtlCache<K, V> {
+    pub fn new(ttl: Duration, negative_ttl: Duration, max_size: usize) -> TtlCache<K, V> {
+        TtlCache {
+            entries: HashMap::new(),
+            ttl,
+            negative_ttl,
+            max_size: max_size.max(1),
+        }
+    }
+
+    // get a cached value, or the cached error of a failed lookup.
+    pub fn get(&mut self, key: &K) -> Option<Result<V, String>> {
+        self.get_with_age(key).map(|(value, _)| value)
+    }
+
+    // get a cached value with the time since it was cached.
+    pub fn get_with_age(&mut self, key: &K) -> Option<(Result<V, String>, Duration)> {
+        let now = Instant::now();
+        match self.entries.get_mut(key) {
+            Some(entry) if entry.expires_at > now => {
+                entry.last_accessed = now;
+                Some((entry.value.clone(), now - entry.inserted_at))
+            }
+            Some(_) => {
+                self.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        let ttl = self.ttl;
+        self.put(key, Ok(value), ttl);
+    }
+
+    pub fn insert_with_ttl(&mut self, key: K, value: V, ttl: Duration) {
+        self.put(key, Ok(value), ttl);
+    }
+
+    pub fn insert_failure(&mut self, key: K, error: String) {
+        let ttl = self.negative_ttl;
+        self.put(key, Err(error), ttl);
+    }
+
+    // remove all entries matching the predicate, return the number of removed entries.
+    pub fn remove_where<F: Fn(&K) -> bool>(&mut self, predicate: F) -> usize {
+        let size = self.entries.len();
+        self.entries.retain(|key, _| !predicate(key));
+        size - self.entries.len()
+    }
+
+    fn put(&mut self, key: K, value: Result<V, String>, ttl: Duration) {
+        let now = Instant::now();
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.max_size {
+            self.entries.retain(|_, entry| entry.expires_at > now);
+        }
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.max_size {
+            let lru_key = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_accessed)
+                .map(|(key, _)| key.clone());
+            if let Some(lru_key) = lru_key {
+                self.entries.remove(&lru_key);
+            }
+        }
+
+        self.entries.insert(
+            key,
+            CacheEntry {
+                value,
+                inserted_at: now,
+                expires_at: now + ttl,
+                last_accessed: now,
+            },
+        );
+    }
+}
+
+// get a cached value, or load and cache it. Failures are cached with the negative ttl.
+pub async fn get_or_load<K, V, F>(
+    cache: &Mutex<TtlCache<K, V>>,
+    key: K,
+    load: F,
+) -> Result<V, Error>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    F: Future<Output = Result<V, Error>>,
+{
+    let cached = cache.lock().await.get(&key);
+    match cached {
+        Some(Ok(value)) => Ok(value),
+        Some(Err(err)) => Err(err.into()),
+        None => match load.await {
+            Ok(value) => {
+                cache.lock().await.insert(key, value.clone());
+                Ok(value)
+            }
+            Err(err) => {
+                cache.lock().await.insert_failure(key, err.to_string());
+                Err(err)
+            }
+        },
+    }
+}
+
+// get_or_load for caches of response data, hits are reported in the response meta.
+pub async fn get_or_load_data<K, V, F>(
+    cache: &Mutex<TtlCache<K, V>>,
+    key: K,
+    load: F,
+) -> Result<V, Error>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    F: Future<Output = Result<V, Error>>,
+{
+    let cached = cache.lock().await.get_with_age(&key);
+    match cached {
+        Some((value, age)) => {
+            record_cache_hit(age);
+            value.map_err(|err| err.into())
+        }
+        None => get_or_load(cache, key, load).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread::sleep, time::Duration};
+
+    use crate::common::cache::TtlCache;
+
+    #[test]
+    fn test_expire_entries() {
+        let mut cache = TtlCache::new(Duration::from_millis(50), Duration::from_millis(10), 8);
+        cache.insert(1, "one".to_string());
+        cache.insert_failure(2, "not found".to_string());
+        assert_eq!(cache.get(&1), Some(Ok("one".to_string())));
+        assert_eq!(cache.get(&2), Some(Err("not found".to_string())));
+
+        sleep(Duration::from_millis(20));
+        assert_eq!(cache.get(&1), Some(Ok("one".to_string())));
+        assert_eq!(cache.get(&2), None);
+
+        sleep(Duration::from_millis(40));
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.remove_where(|_| true), 0);
+    }
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let mut cache = TtlCache::new(Duration::from_secs(60), Duration::from_secs(60), 2);
+        cache.insert(1, 1);
+        sleep(Duration::from_millis(2));
+        cache.insert(2, 2);
+        sleep(Duration::from_millis(2));
+        cache.get(&1);
+        cache.insert(3, 3);
+
+        assert_eq!(cache.get(&1), Some(Ok(1)));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&3), Some(Ok(3)));
+    }
+
+    #[test]
+    fn test_remove_entries() {
+        let mut cache = TtlCache::new(Duration::from_secs(60), Duration::from_secs(60), 8);
+        cache.insert((1, 10), 10);
+        cache.insert((1, 11), 11);
+        cache.insert((2, 10), 10);
+
+        assert_eq!(cache.remove_where(|key| *key == (2, 10)), 1);
+        assert_eq!(cache.remove_where(|key| key.0 == 1), 2);
+        assert_eq!(cache.get(&(1, 10)), None);
+        assert_eq!(cache.remove_where(|_| true), 0);
+    }
+}
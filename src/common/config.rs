@@ -0,0 +1,528 @@
+use std::{env, fs, io::ErrorKind};
+
+use ethers::types::Address;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Deserializer};
+
+use super::{
+    address::{get_contract_address, get_contract_start_block},
+    defines::{
+        Error, NetworkType, SupportedContractType, DEFAULT_CONFIG_PATH, HTTP_CACHE_MAX_ENTRIES,
+        NAMILAND_GAME_ITEM_COLLECTION_DESCRIPTION, NAMILAND_GAME_ITEM_NFT_IDS, SUPPORTED_NETWORKS,
+    },
+};
+
+static CONFIG: OnceCell<Config> = OnceCell::new();
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    // the NamiLand game item collection when it is not configured.
+    #[serde(default = "default_erc1155_collections")]
+    pub erc1155_collections: Vec<ERC1155CollectionConfig>,
+    #[serde(default)]
+    pub erc721_collections: Vec<ERC721CollectionConfig>,
+    // tokens listed in wallet inventory.
+    #[serde(default)]
+    pub erc20_tokens: Vec<ERC20TokenConfig>,
+    // dex pairs the usd prices of tokens are derived from.
+    #[serde(default)]
+    pub price_pairs: Vec<PricePairConfig>,
+    // dex pairs players provide liquidity to, served by the lp position api.
+    #[serde(default)]
+    pub liquidity_pairs: Vec<LiquidityPairConfig>,
+    // NECO stake contracts, stake info, stake webhooks and stake events need them.
+    #[serde(default)]
+    pub stake_contracts: Vec<StakeContractConfig>,
+    #[serde(default)]
+    pub http_cache: HttpCacheConfig,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenIdSource {
+    // only the configured `token_ids`.
+    #[default]
+    Static,
+    // `token_ids` and the ids registered by `getTokenIdsLength`/`getTokenIdByIndex`.
+    Contract,
+    // `token_ids` and the ids found in transfer events.
+    Events,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ERC1155CollectionConfig {
+    // identifier of the collection in apis, unique on a network.
+    pub id: String,
+    #[serde(rename = "chain_id", deserialize_with = "deserialize_network")]
+    pub network: NetworkType,
+    pub address: Address,
+    // game client owning the collection, the `game_client` of ownership apis.
+    pub game_client: u8,
+    #[serde(default)]
+    pub token_id_source: TokenIdSource,
+    #[serde(default)]
+    pub token_ids: Vec<u64>,
+    // block to scan transfer events from, the deployment block. Holders and the `events`
+    // token id source are not available without it.
+    pub start_block: Option<u64>,
+    // metadata uri overriding `uri(id)` of the contract, `{id}` is substituted.
+    pub metadata_uri: Option<String>,
+    // description of the collection in `contractURI`.
+    pub description: Option<String>,
+    // whether the contract locks token ids by `getLockedTokenIds*`, collections without
+    // locking have no locked ids.
+    #[serde(default = "default_token_locking")]
+    pub token_locking: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ERC721CollectionConfig {
+    pub id: String,
+    #[serde(rename = "chain_id", deserialize_with = "deserialize_network")]
+    pub network: NetworkType,
+    pub address: Address,
+    // block to scan transfer events from, the deployment block. Tokens of a collection
+    // which is not enumerable are not listed without it.
+    pub start_block: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ERC20TokenConfig {
+    pub symbol: String,
+    #[serde(rename = "chain_id", deserialize_with = "deserialize_network")]
+    pub network: NetworkType,
+    pub address: Address,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StakeContractConfig {
+    #[serde(rename = "chain_id", deserialize_with = "deserialize_network")]
+    pub network: NetworkType,
+    pub address: Address,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PricePairConfig {
+    // symbol priced by the pair, native BNB and ETH are priced by their wrapped tokens.
+    pub symbol: String,
+    #[serde(rename = "chain_id", deserialize_with = "deserialize_network")]
+    pub network: NetworkType,
+    // PancakeSwap V2 compatible pair of the token against a USD stablecoin like BUSD or USDT.
+    pub pair: Address,
+    // the priced token of the pair, the other one is the stablecoin.
+    pub token: Address,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LiquidityPairConfig {
+    // identifier of the pair in apis, unique on a network.
+    pub id: String,
+    #[serde(rename = "chain_id", deserialize_with = "deserialize_network")]
+    pub network: NetworkType,
+    // PancakeSwap V2 compatible pair, also the lp token.
+    pub address: Address,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HttpCacheConfig {
+    #[serde(default = "default_http_cache_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_http_cache_max_entries")]
+    pub max_entries: usize,
+    // the first rule matching the path applies, paths without a rule are not cached.
+    #[serde(default = "default_http_cache_rules")]
+    pub rules: Vec<HttpCacheRule>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HttpCacheRule {
+    pub path_prefix: String,
+    // seconds results are cached on the server and by clients, 0 disables caching.
+    pub max_age: u64,
+    // key cached results by the latest block of the network as well, for chain state.
+    #[serde(default)]
+    pub per_block: bool,
+}
+
+impl Default for HttpCacheConfig {
+    fn default() -> Self {
+        HttpCacheConfig {
+            enabled: default_http_cache_enabled(),
+            max_entries: default_http_cache_max_entries(),
+            rules: default_http_cache_rules(),
+        }
+    }
+}
+
+impl HttpCacheConfig {
+    pub fn find_rule(&self, path: &str) -> Option<&HttpCacheRule> {
+        self.rules
+            .iter()
+            .find(|rule| path.starts_with(&rule.path_prefix))
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            erc1155_collections: default_erc1155_collections(),
+            erc721_collections: vec![],
+            erc20_tokens: vec![],
+            price_pairs: vec![],
+            liquidity_pairs: vec![],
+            stake_contracts: vec![],
+            http_cache: HttpCacheConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn parse(content: &str) -> Result<Config, Error> {
+        let config: Config = toml::from_str(content)?;
+        for (index, collection) in config.erc1155_collections.iter().enumerate() {
+            let duplicated = config.erc1155_collections[..index].iter().any(|other| {
+                other.network == collection.network
+                    && (other.id == collection.id || other.address == collection.address)
+            });
+            if duplicated {
+                return Err(format!("collection {} is configured twice", collection.id).into());
+            }
+            // block 0 is the genesis block, not a deployment block.
+            if collection.start_block == Some(0) {
+                return Err(format!(
+                    "start_block of collection {} must be its deployment block",
+                    collection.id
+                )
+                .into());
+            }
+            if collection.token_id_source == TokenIdSource::Events
+                && collection.start_block.is_none()
+            {
+                return Err(format!(
+                    "collection {} with token_id_source \"events\" needs start_block",
+                    collection.id
+                )
+                .into());
+            }
+        }
+        for collection in &config.erc721_collections {
+            if collection.start_block == Some(0) {
+                return Err(format!(
+                    "start_block of collection {} must be its deployment block",
+                    collection.id
+                )
+                .into());
+            }
+        }
+        Ok(config)
+    }
+
+    // the NECO stake contract of the network.
+    pub fn stake_contract(&self, network: NetworkType) -> Option<Address> {
+        self.stake_contracts
+            .iter()
+            .find(|contract| contract.network == network)
+            .map(|contract| contract.address)
+    }
+
+    // find an ERC721 collection on the network by its contract address.
+    pub fn find_erc721_collection(
+        &self,
+        network: NetworkType,
+        address: Address,
+    ) -> Option<&ERC721CollectionConfig> {
+        self.erc721_collections
+            .iter()
+            .find(|collection| collection.network == network && collection.address == address)
+    }
+
+    // find a liquidity pair on the network by its id or pair address.
+    pub fn find_liquidity_pair(
+        &self,
+        network: NetworkType,
+        pair: &str,
+    ) -> Option<&LiquidityPairConfig> {
+        let address = pair.parse::<Address>().ok();
+        self.liquidity_pairs.iter().find(|config| {
+            config.network == network && (config.id == pair || Some(config.address) == address)
+        })
+    }
+
+    // find the price pair of a token on the network, tokens on other networks, e.g. the
+    // testnet token of the same symbol, have no price.
+    pub fn find_price_pair(
+        &self,
+        network: NetworkType,
+        token: Address,
+    ) -> Option<&PricePairConfig> {
+        self.price_pairs
+            .iter()
+            .find(|pair| pair.network == network && pair.token == token)
+    }
+
+    // find a collection on the network by its id or contract address.
+    pub fn find_collection(
+        &self,
+        network: NetworkType,
+        collection: &str,
+    ) -> Option<&ERC1155CollectionConfig> {
+        let address = collection.parse::<Address>().ok();
+        self.erc1155_collections.iter().find(|config| {
+            config.network == network
+                && (config.id == collection || Some(config.address) == address)
+        })
+    }
+
+    pub fn game_client_collection(
+        &self,
+        network: NetworkType,
+        game_client: u8,
+    ) -> Option<&ERC1155CollectionConfig> {
+        self.erc1155_collections
+            .iter()
+            .find(|config| config.network == network && config.game_client == game_client)
+    }
+
+    // select a collection by id or address, by game client, or the default one in this order.
+    pub fn select_collection(
+        &self,
+        network: NetworkType,
+        collection: Option<&str>,
+        game_client: Option<u8>,
+    ) -> Option<&ERC1155CollectionConfig> {
+        match (collection, game_client) {
+            (Some(collection), _) => self.find_collection(network, collection),
+            (None, Some(game_client)) => self.game_client_collection(network, game_client),
+            (None, None) => self.default_collection(network),
+        }
+    }
+
+    // the collection served by apis without a collection parameter, the first one on the network.
+    pub fn default_collection(&self, network: NetworkType) -> Option<&ERC1155CollectionConfig> {
+        self.erc1155_collections
+            .iter()
+            .find(|config| config.network == network)
+    }
+}
+
+// load the config file at `CONFIG_PATH` env, the default config is used when the file is missing.
+pub fn load_config() -> Result<(), Error> {
+    let path = env::var("CONFIG_PATH").unwrap_or_else(|_| DEFAULT_CONFIG_PATH.to_string());
+    let config = match fs::read_to_string(&path) {
+        Ok(content) => Config::parse(&content)?,
+        Err(err) if err.kind() == ErrorKind::NotFound => Config::default(),
+        Err(err) => return Err(err.into()),
+    };
+    CONFIG.set(config).map_err(|_| "config is loaded already")?;
+    Ok(())
+}
+
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+fn deserialize_network<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<NetworkType, D::Error> {
+    let chain_id = u64::deserialize(deserializer)?;
+    NetworkType::from_chain_id(chain_id)
+        .ok_or_else(|| serde::de::Error::custom(format!("chain id {} is not supported", chain_id)))
+}
+
+fn default_erc1155_collections() -> Vec<ERC1155CollectionConfig> {
+    let contract_type = SupportedContractType::NamiLandGameItemNFT;
+    SUPPORTED_NETWORKS
+        .iter()
+        .filter_map(|network| {
+            Some(ERC1155CollectionConfig {
+                id: "namiland-game-item".to_string(),
+                network: *network,
+                address: get_contract_address(contract_type, *network).ok()?,
+                game_client: 0,
+                token_id_source: TokenIdSource::Contract,
+                token_ids: NAMILAND_GAME_ITEM_NFT_IDS
+                    .iter()
+                    .map(|id| *id as u64)
+                    .collect(),
+                start_block: get_contract_start_block(contract_type, *network).ok(),
+                metadata_uri: None,
+                description: Some(NAMILAND_GAME_ITEM_COLLECTION_DESCRIPTION.to_string()),
+                token_locking: true,
+            })
+        })
+        .collect()
+}
+
+fn default_token_locking() -> bool {
+    true
+}
+
+fn default_http_cache_enabled() -> bool {
+    true
+}
+
+fn default_http_cache_max_entries() -> usize {
+    HTTP_CACHE_MAX_ENTRIES
+}
+
+// metadata is long-lived, chain state is short-lived and keyed by block.
+fn default_http_cache_rules() -> Vec<HttpCacheRule> {
+    let rule = |path_prefix: &str, max_age: u64, per_block: bool| HttpCacheRule {
+        path_prefix: path_prefix.to_string(),
+        max_age,
+        per_block,
+    };
+    vec![
+        rule("/v1/namiland-game-item-nft/metadata", 3600, false),
+        rule("/v1/namiland-game-item-nft/contract-uri", 3600, false),
+        rule("/v1/erc721/metadata", 3600, false),
+        rule("/v1/namiland-game-item-nft/catalogue", 300, false),
+        rule("/v1/namiland-game-item-nft/collection", 60, false),
+        rule("/v1/namiland-game-item-nft/ownership", 5, true),
+        rule("/v1/namiland-game-item-nft/holders", 10, true),
+        rule("/v1/namiland-game-item-nft/locked", 10, true),
+        rule("/v1/erc20/balance", 5, true),
+        rule("/v1/erc721/ownership", 5, true),
+        rule("/v1/erc721/owner", 5, true),
+        // the network is a path segment of the stake route, results are cached by time.
+        rule("/v1/neco-staked-info", 5, false),
+        rule("/v1/lp/position", 5, true),
+        rule("/v1/prices", 10, false),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::Address;
+
+    use crate::common::{
+        config::{Config, TokenIdSource},
+        defines::NetworkType,
+    };
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse(
+            r#"
+            [[erc1155_collections]]
+            id = "namiland-game-item"
+            chain_id = 5
+            address = "0x5FaB721a3fa13c0219EB24C121f9F6482f64f274"
+            game_client = 0
+            token_id_source = "contract"
+            token_ids = [10001, 10002]
+
+            [[erc1155_collections]]
+            id = "namiland-badge"
+            chain_id = 97
+            address = "0x0000000000000000000000000000000000000001"
+            game_client = 1
+            metadata_uri = "https://example.com/{id}.json"
+
+            [[liquidity_pairs]]
+            id = "bnb-busd"
+            chain_id = 56
+            address = "0x58F876857a02D6762E0101bb5C46A8c1ED44Dc16"
+
+            [[price_pairs]]
+            symbol = "BNB"
+            chain_id = 56
+            pair = "0x58F876857a02D6762E0101bb5C46A8c1ED44Dc16"
+            token = "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c"
+            "#,
+        )
+        .unwrap();
+
+        let collection = config
+            .find_collection(
+                NetworkType::GoerliTestnet,
+                "0x5fab721a3fa13c0219eb24c121f9f6482f64f274",
+            )
+            .unwrap();
+        assert_eq!(collection.id, "namiland-game-item");
+        assert_eq!(collection.token_id_source, TokenIdSource::Contract);
+        assert!(config
+            .find_collection(NetworkType::GoerliTestnet, "namiland-badge")
+            .is_none());
+        assert_eq!(
+            config
+                .game_client_collection(NetworkType::BSCTestNetwork, 1)
+                .map(|collection| collection.token_id_source),
+            Some(TokenIdSource::Static)
+        );
+        let wbnb = NetworkType::BSCMainNetwork.wrapped_native_token().unwrap();
+        assert_eq!(
+            config
+                .find_price_pair(NetworkType::BSCMainNetwork, wbnb)
+                .map(|pair| pair.symbol.as_str()),
+            Some("BNB")
+        );
+        assert!(config
+            .find_price_pair(NetworkType::BSCTestNetwork, wbnb)
+            .is_none());
+        assert!(config
+            .find_price_pair(NetworkType::BSCMainNetwork, Address::zero())
+            .is_none());
+        assert!(config
+            .find_liquidity_pair(
+                NetworkType::BSCMainNetwork,
+                "0x58f876857a02d6762e0101bb5c46a8c1ed44dc16"
+            )
+            .is_some());
+        assert!(config
+            .find_liquidity_pair(NetworkType::BSCTestNetwork, "bnb-busd")
+            .is_none());
+        assert!(Config::parse("[[erc1155_collections]]\nid = \"a\"\nchain_id = 2\naddress = \"0x0000000000000000000000000000000000000001\"\ngame_client = 0").is_err());
+        // event scans need the deployment block.
+        assert!(Config::parse("[[erc1155_collections]]\nid = \"a\"\nchain_id = 5\naddress = \"0x0000000000000000000000000000000000000001\"\ngame_client = 0\ntoken_id_source = \"events\"").is_err());
+        assert!(Config::parse("[[erc1155_collections]]\nid = \"a\"\nchain_id = 5\naddress = \"0x0000000000000000000000000000000000000001\"\ngame_client = 0\nstart_block = 0").is_err());
+        let config = Config::parse("[[stake_contracts]]\nchain_id = 97\naddress = \"0x0000000000000000000000000000000000000009\"").unwrap();
+        assert_eq!(
+            config.stake_contract(NetworkType::BSCTestNetwork),
+            Some(Address::from_low_u64_be(9))
+        );
+        assert_eq!(config.stake_contract(NetworkType::BSCMainNetwork), None);
+        assert!(Config::parse("[[erc721_collections]]\nid = \"a\"\nchain_id = 56\naddress = \"0x0000000000000000000000000000000000000001\"\nstart_block = 0").is_err());
+    }
+
+    #[test]
+    fn test_default_config() {
+        let config = Config::parse("").unwrap();
+        assert!(config
+            .default_collection(NetworkType::GoerliTestnet)
+            .is_some_and(|collection| collection.token_locking));
+        assert!(config.http_cache.enabled);
+        assert!(config
+            .http_cache
+            .find_rule("/v1/erc20/balance/batch")
+            .is_some_and(|rule| rule.per_block));
+        assert!(config
+            .http_cache
+            .find_rule("/v1/neco-staked-info/0/0x0000000000000000000000000000000000000001")
+            .is_some_and(|rule| !rule.per_block));
+        assert!(config.http_cache.find_rule("/v1/wallet/stream").is_none());
+    }
+
+    #[test]
+    fn test_parse_http_cache_config() {
+        let config = Config::parse(
+            r#"
+            [http_cache]
+            max_entries = 10
+
+            [[http_cache.rules]]
+            path_prefix = "/v1/erc20/balance"
+            max_age = 2
+            per_block = true
+            "#,
+        )
+        .unwrap();
+        assert!(config.http_cache.enabled);
+        assert_eq!(config.http_cache.max_entries, 10);
+        assert_eq!(config.http_cache.rules.len(), 1);
+        assert!(config
+            .http_cache
+            .find_rule("/v1/namiland-game-item-nft/metadata/1")
+            .is_none());
+    }
+}
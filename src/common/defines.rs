@@ -1,3 +1,4 @@
+use ethers::types::Address;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::fmt::Formatter;
@@ -20,28 +21,60 @@ impl Default for NetworkType {
     }
 }
 
-// implement the Display trait to convert enum to a string.
-impl fmt::Display for NetworkType {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+pub const SUPPORTED_NETWORKS: [NetworkType; 4] = [
+    NetworkType::EthereumMainnet,
+    NetworkType::GoerliTestnet,
+    NetworkType::BSCMainNetwork,
+    NetworkType::BSCTestNetwork,
+];
+
+impl NetworkType {
+    // get network type by EVM chain id.
+    pub fn from_chain_id(chain_id: u64) -> Option<NetworkType> {
+        match chain_id {
+            1 => Some(NetworkType::EthereumMainnet),
+            5 => Some(NetworkType::GoerliTestnet),
+            56 => Some(NetworkType::BSCMainNetwork),
+            97 => Some(NetworkType::BSCTestNetwork),
+            _ => None,
+        }
+    }
+
+    pub fn chain_id(&self) -> u64 {
         match self {
-            NetworkType::EthereumMainnet => write!(f, "EthereumMainnet"),
-            NetworkType::GoerliTestnet => write!(f, "GoerliTestnet"),
-            NetworkType::BSCMainNetwork => write!(f, "bsc_main_network"),
-            NetworkType::BSCTestNetwork => write!(f, "bsc_test_network"),
+            NetworkType::EthereumMainnet => 1,
+            NetworkType::GoerliTestnet => 5,
+            NetworkType::BSCMainNetwork => 56,
+            NetworkType::BSCTestNetwork => 97,
         }
     }
-}
 
-#[derive(Debug)]
-pub enum GameClient {
-    NamiLand,
+    pub fn native_symbol(&self) -> &'static str {
+        match self {
+            NetworkType::EthereumMainnet | NetworkType::GoerliTestnet => "ETH",
+            NetworkType::BSCMainNetwork | NetworkType::BSCTestNetwork => "BNB",
+        }
+    }
+
+    // the wrapped native token native balances are priced by, testnets have no prices.
+    pub fn wrapped_native_token(&self) -> Option<Address> {
+        let address = match self {
+            NetworkType::EthereumMainnet => "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+            NetworkType::BSCMainNetwork => "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c",
+            NetworkType::GoerliTestnet | NetworkType::BSCTestNetwork => return None,
+        };
+        address.parse().ok()
+    }
 }
 
 // implement the Display trait to convert enum to a string.
-impl fmt::Display for GameClient {
+impl fmt::Display for NetworkType {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            GameClient::NamiLand => write!(f, "namiland"),
+            NetworkType::EthereumMainnet => write!(f, "EthereumMainnet"),
+            NetworkType::GoerliTestnet => write!(f, "GoerliTestnet"),
+            NetworkType::BSCMainNetwork => write!(f, "bsc_main_network"),
+            NetworkType::BSCTestNetwork => write!(f, "bsc_test_network"),
         }
     }
 }
@@ -70,6 +103,113 @@ pub const NAMILAND_GAME_ITEM_NFT_IDS: [i32; 48] = [
     14008, 14009, 14010, 14011, 14012, 14013, 14014, 14015, 14016,
 ];
 
+// Config file read at startup when `CONFIG_PATH` env is not set.
+pub const DEFAULT_CONFIG_PATH: &str = "./config.toml";
+
+// Max number of calls aggregated into a single multicall request.
+pub const MULTICALL_BATCH_SIZE: usize = 100;
+
+pub const NAMILAND_GAME_ITEM_COLLECTION_DESCRIPTION: &str =
+    "Game items of NamiLand, including fishing rods, baits and other props.";
+
+// Cache of nft uri and metadata, failed lookups are cached with a shorter ttl.
+pub const NFT_METADATA_CACHE_TTL_SECS: u64 = 3600;
+pub const NFT_METADATA_NEGATIVE_CACHE_TTL_SECS: u64 = 60;
+pub const NFT_METADATA_CACHE_MAX_SIZE: usize = 4096;
+
+// ERC165 interface id of ERC721Enumerable.
+pub const ERC721_ENUMERABLE_INTERFACE_ID: [u8; 4] = [0x78, 0x0e, 0x9d, 0x63];
+
+// Interval of refreshing metadata of all known nft ids in background, shorter than
+// the cache ttl to keep the cache warm.
+pub const NFT_METADATA_REFRESH_INTERVAL_SECS: u64 = 600;
+
+// Interval of polling `URI` events and `baseTokenURI` changes to invalidate metadata cache.
+pub const NFT_URI_WATCH_INTERVAL_SECS: u64 = 30;
+
+// Gateways to fetch `ipfs://` and `ar://` uris, overridden by the comma separated
+// `IPFS_GATEWAYS` and `ARWEAVE_GATEWAYS` env.
+pub const DEFAULT_IPFS_GATEWAYS: [&str; 3] = [
+    "https://ipfs.io",
+    "https://cloudflare-ipfs.com",
+    "https://gateway.pinata.cloud",
+];
+pub const DEFAULT_ARWEAVE_GATEWAYS: [&str; 1] = ["https://arweave.net"];
+
+// Max number of wallet addresses in one multi-address request.
+pub const MAX_ADDRESSES_PER_REQUEST: usize = 20;
+
+// ENS registry of `.eth` names, deployed at the same address on ethereum mainnet and goerli.
+pub const ENS_REGISTRY_ADDRESS: &str = "0x00000000000C2E074eC69A0dFb2997BA6C7d2e1e";
+
+// Space ID registry of `.bnb` names on bsc mainnet.
+pub const SPACE_ID_REGISTRY_ADDRESS: &str = "0x08CEd32a7f3eeC915Ba84415e9C07a7286977956";
+
+// Usd prices derived from dex pair reserves are cached shortly.
+pub const PRICE_CACHE_TTL_SECS: u64 = 30;
+pub const PRICE_NEGATIVE_CACHE_TTL_SECS: u64 = 10;
+pub const PRICE_CACHE_MAX_SIZE: usize = 256;
+
+// Whether an address is a contract is cached, failed lookups for a shorter time.
+pub const CONTRACT_CODE_CACHE_TTL_SECS: u64 = 3600;
+pub const CONTRACT_CODE_NEGATIVE_CACHE_TTL_SECS: u64 = 60;
+pub const CONTRACT_CODE_CACHE_MAX_SIZE: usize = 4096;
+
+// Resolved names and primary names are cached, failed lookups for a shorter time.
+pub const NAME_CACHE_TTL_SECS: u64 = 600;
+pub const NAME_NEGATIVE_CACHE_TTL_SECS: u64 = 60;
+pub const NAME_CACHE_MAX_SIZE: usize = 4096;
+
+// Max number of nft ids in one batch metadata request, and how many are fetched at once.
+pub const NFT_METADATA_BATCH_MAX_SIZE: usize = 200;
+pub const NFT_METADATA_FETCH_CONCURRENCY: usize = 8;
+
+// Local disk cache of nft images, overridden by the `IMAGE_CACHE_DIR` env.
+pub const DEFAULT_IMAGE_CACHE_DIR: &str = "./image-cache";
+// Allowed thumbnail sizes in pixels, overridden by the `IMAGE_THUMBNAIL_SIZES` env.
+pub const DEFAULT_IMAGE_THUMBNAIL_SIZES: [u32; 4] = [64, 128, 256, 512];
+pub const IMAGE_CACHE_CONTROL: &str = "public, max-age=86400";
+// Source images and metadata larger than these are rejected before they are decoded.
+pub const IMAGE_SOURCE_MAX_BYTES: usize = 10 * 1024 * 1024;
+pub const NFT_METADATA_MAX_BYTES: usize = 4 * 1024 * 1024;
+
+// Cached v1 responses, see `[http_cache]` of the config.
+pub const HTTP_CACHE_MAX_ENTRIES: usize = 4096;
+// The latest block of a network is polled at most once in the ttl for block keyed results.
+pub const HTTP_CACHE_BLOCK_TTL_MILLIS: u64 = 1000;
+
+// Max number of ERC721 tokens listed for one owner, and of tokens received by transfer
+// events checked for ownership.
+pub const ERC721_OWNERSHIP_MAX_TOKENS: u64 = 500;
+pub const ERC721_TRANSFER_CANDIDATES_MAX: usize = 5000;
+
+// Max block range of a single eth_getLogs request when scanning NFT transfer events.
+pub const NFT_TRANSFER_LOG_BLOCK_RANGE: u64 = 5000;
+
+// The deepest confirmations accepted by the `finality` param of balance, ownership and stake apis.
+pub const FINALITY_MAX_CONFIRMATIONS: u64 = 1000;
+
+// Block polling interval, keepalive interval and event buffer of the wallet asset stream.
+pub const WALLET_STREAM_POLL_SECS: u64 = 3;
+pub const WALLET_STREAM_KEEPALIVE_SECS: u64 = 15;
+pub const WALLET_STREAM_CHANNEL_SIZE: usize = 64;
+// The farthest block behind the latest block a stream can resume from.
+pub const WALLET_STREAM_MAX_RESUME_BLOCKS: u64 = 5000;
+
+// Webhook subscriptions and delivery logs, overridden by the `WEBHOOK_STORE_PATH` env.
+pub const DEFAULT_WEBHOOK_STORE_PATH: &str = "./webhooks.json";
+pub const WEBHOOK_POLL_INTERVAL_SECS: u64 = 5;
+pub const WEBHOOK_REQUEST_TIMEOUT_SECS: u64 = 10;
+pub const WEBHOOK_DEFAULT_CONFIRMATIONS: u64 = 3;
+pub const WEBHOOK_MAX_CONFIRMATIONS: u64 = 100;
+// A failed delivery is retried after 10s, 20s, 40s... up to an hour, 8 attempts in total.
+pub const WEBHOOK_RETRY_BASE_SECS: u64 = 10;
+pub const WEBHOOK_RETRY_MAX_SECS: u64 = 3600;
+pub const WEBHOOK_MAX_ATTEMPTS: u32 = 8;
+// Finished deliveries kept in the logs of each subscription.
+pub const WEBHOOK_DELIVERY_LOG_SIZE: usize = 200;
+pub const WEBHOOK_SECRET_MIN_LENGTH: usize = 16;
+
 pub const ETHEREUM_MAINNET_NETWORK_RPC: &str =
     "https://mainnet.infura.io/v3/89f31b5b62a44ed68b4f73c35be6c81f";
 pub const GOERLI_TESTNET_NETWORK_RPC: &str =
@@ -82,4 +222,5 @@ pub enum SupportedContractType {
     NAMIX,
     FISHX,
     NamiLandGameItemNFT,
+    NecoStake,
 }
@@ -0,0 +1,131 @@
+use std::fmt;
+
+use ethers::{
+    abi::Detokenize, contract::builders::ContractCall, prelude::Multicall, providers::Middleware,
+    types::BlockNumber,
+};
+
+use super::{
+    defines::{Error, NetworkType, FINALITY_MAX_CONFIRMATIONS},
+    provider::ProviderManager,
+    request_meta::record_block,
+};
+
+// block state is read at: the latest block, the safe or finalized block reported by the
+// node, or the block with the given confirmations on top of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Finality {
+    Latest,
+    Safe,
+    Finalized,
+    Confirmations(u64),
+}
+
+impl Finality {
+    // parse `latest`, `safe`, `finalized` or a number of confirmations.
+    pub fn parse(value: &str) -> Result<Finality, Error> {
+        match value.trim().to_lowercase().as_str() {
+            "latest" => Ok(Finality::Latest),
+            "safe" => Ok(Finality::Safe),
+            "finalized" => Ok(Finality::Finalized),
+            value => match value.parse::<u64>() {
+                Ok(confirmations) if confirmations <= FINALITY_MAX_CONFIRMATIONS => {
+                    Ok(Finality::Confirmations(confirmations))
+                }
+                _ => Err(format!(
+                    "finality must be latest, safe, finalized or confirmations up to {}",
+                    FINALITY_MAX_CONFIRMATIONS
+                )
+                .into()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Finality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Finality::Latest => write!(f, "latest"),
+            Finality::Safe => write!(f, "safe"),
+            Finality::Finalized => write!(f, "finalized"),
+            Finality::Confirmations(confirmations) => write!(f, "{}", confirmations),
+        }
+    }
+}
+
+// Resolve the `finality` param to the block number reads are pinned to, the latest block
+// without the param. The block is reported in the response meta.
+pub async fn resolve_block_number(
+    network: NetworkType,
+    finality: Option<&str>,
+) -> Result<u64, Error> {
+    let finality = match finality {
+        Some(finality) => Finality::parse(finality)?,
+        None => Finality::Latest,
+    };
+    let client = match ProviderManager::instance().get_provider(network) {
+        Some(client) => client,
+        None => return Err("get provider failed".into()),
+    };
+    let tag = match finality {
+        Finality::Latest => BlockNumber::Latest,
+        Finality::Confirmations(confirmations) => {
+            let latest_block = client.get_block_number().await?.as_u64();
+            BlockNumber::Number(latest_block.saturating_sub(confirmations).into())
+        }
+        Finality::Safe => BlockNumber::Safe,
+        Finality::Finalized => BlockNumber::Finalized,
+    };
+    // nodes without safe and finalized tags reject them.
+    let block = client.get_block(tag).await.map_err(|err| {
+        format!(
+            "finality {} is not supported on {}: {}",
+            finality, network, err
+        )
+    })?;
+    match block.and_then(|block| Some((block.number?, block.hash))) {
+        Some((number, hash)) => {
+            record_block(number.as_u64(), hash.map(|hash| format!("{:?}", hash)));
+            Ok(number.as_u64())
+        }
+        None => Err(format!("finality {} is not supported on {}", finality, network).into()),
+    }
+}
+
+// pin a contract call to the resolved block, calls without a block read the latest block.
+pub fn at_block<M: Middleware, D: Detokenize>(
+    call: ContractCall<M, D>,
+    block_number: Option<u64>,
+) -> ContractCall<M, D> {
+    match block_number {
+        Some(block_number) => call.block(block_number),
+        None => call,
+    }
+}
+
+// pin the calls of a multicall to the resolved block.
+pub fn multicall_at_block<M: Middleware>(
+    multicall: Multicall<M>,
+    block_number: Option<u64>,
+) -> Multicall<M> {
+    match block_number {
+        Some(block_number) => multicall.block(block_number),
+        None => multicall,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::finality::Finality;
+
+    #[test]
+    fn test_parse_finality() {
+        assert_eq!(Finality::parse("latest").unwrap(), Finality::Latest);
+        assert_eq!(Finality::parse(" Finalized ").unwrap(), Finality::Finalized);
+        assert_eq!(Finality::parse("safe").unwrap(), Finality::Safe);
+        assert_eq!(Finality::parse("15").unwrap(), Finality::Confirmations(15));
+        assert!(Finality::parse("100000").is_err());
+        assert!(Finality::parse("pending").is_err());
+        assert!(Finality::parse("-1").is_err());
+    }
+}
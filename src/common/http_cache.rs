@@ -0,0 +1,398 @@
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::{boxed, Body, Bytes, HttpBody},
+    http::{header, HeaderMap, HeaderValue, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use ethers::{providers::Middleware, utils::hex};
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+
+use super::{
+    cache::{get_or_load, TtlCache},
+    config::{config, HttpCacheRule},
+    defines::{Error, NetworkType, HTTP_CACHE_BLOCK_TTL_MILLIS},
+    provider::ProviderManager,
+};
+
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    body: Value,
+    etag: String,
+    cached_at: Instant,
+}
+
+// entries are inserted with the max age of their rule.
+static RESPONSE_CACHES: Lazy<Mutex<TtlCache<String, CachedResponse>>> = Lazy::new(|| {
+    Mutex::new(TtlCache::new(
+        Duration::ZERO,
+        Duration::ZERO,
+        config().http_cache.max_entries,
+    ))
+});
+
+static LATEST_BLOCK_CACHES: Lazy<Mutex<TtlCache<NetworkType, u64>>> = Lazy::new(|| {
+    let ttl = Duration::from_millis(HTTP_CACHE_BLOCK_TTL_MILLIS);
+    Mutex::new(TtlCache::new(ttl, ttl, 64))
+});
+
+// Cache GET responses of the v1 routes by the `[http_cache]` rules, with `ETag` and
+// `Cache-Control` headers. `If-None-Match` matching the etag of the data returns 304.
+pub async fn http_cache(request: Request<Body>, next: Next<Body>) -> Response {
+    let started_at = Instant::now();
+    let settings = &config().http_cache;
+    let rule = match settings.find_rule(request.uri().path()) {
+        Some(rule) if settings.enabled && rule.max_age > 0 && request.method() == Method::GET => {
+            rule.clone()
+        }
+        _ => return next.run(request).await,
+    };
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+    // a client asking to revalidate skips the cached result, the fresh one is cached.
+    let no_cache = request
+        .headers()
+        .get(header::CACHE_CONTROL)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("no-cache"));
+
+    // chain state is keyed by the latest block, a result is not served across blocks.
+    let mut key = format!(
+        "{}?{}",
+        request.uri().path(),
+        canonical_query(request.uri().query())
+    );
+    // results of a rule keyed by block are not cached without the `chain_id` param.
+    if rule.per_block {
+        let block_number = match query_network(request.uri().query()) {
+            Some(network) => latest_block_number(network).await.ok(),
+            None => None,
+        };
+        match block_number {
+            Some(block_number) => key = format!("{}#{}", key, block_number),
+            None => return next.run(request).await,
+        }
+    }
+
+    if !no_cache {
+        let cached = RESPONSE_CACHES.lock().await.get(&key);
+        if let Some(Ok(cached)) = cached {
+            let mut body = cached.body;
+            mark_cached(&mut body, cached.cached_at.elapsed(), started_at.elapsed());
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            );
+            let body = Body::from(serde_json::to_vec(&body).unwrap_or_default());
+            return cached_response(&rule, &cached.etag, if_none_match.as_ref(), headers, body);
+        }
+    }
+
+    let response = next.run(request).await;
+    // streams and images are passed through, only json bodies are buffered.
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if response.status() != StatusCode::OK || !is_json {
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let bytes = match read_body(body).await {
+        Ok(bytes) => bytes,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    let body: Value = match serde_json::from_slice(&bytes) {
+        Ok(body) => body,
+        Err(_) => return Response::from_parts(parts, Body::from(bytes)).into_response(),
+    };
+    // errors are reported in the `status` of the body, they are not cached.
+    if body.get("status").and_then(Value::as_u64) != Some(StatusCode::OK.as_u16() as u64) {
+        let mut response = Response::from_parts(parts, Body::from(bytes)).into_response();
+        response
+            .headers_mut()
+            .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        return response;
+    }
+
+    let etag = data_etag(&body);
+    RESPONSE_CACHES.lock().await.insert_with_ttl(
+        key,
+        CachedResponse {
+            body,
+            etag: etag.clone(),
+            cached_at: Instant::now(),
+        },
+        Duration::from_secs(rule.max_age),
+    );
+    cached_response(
+        &rule,
+        &etag,
+        if_none_match.as_ref(),
+        parts.headers,
+        Body::from(bytes),
+    )
+}
+
+// drop cached responses of paths starting with the prefix, when the data they were built
+// from is invalidated. Paths are compared case insensitively, for addresses in paths.
+pub async fn invalidate_responses(path_prefix: &str) -> usize {
+    let path_prefix = path_prefix.to_lowercase();
+    RESPONSE_CACHES
+        .lock()
+        .await
+        .remove_where(|key| key.to_lowercase().starts_with(&path_prefix))
+}
+
+// a 304 when `If-None-Match` matches the etag, otherwise the body with caching headers.
+fn cached_response(
+    rule: &HttpCacheRule,
+    etag: &str,
+    if_none_match: Option<&HeaderValue>,
+    mut headers: HeaderMap,
+    body: Body,
+) -> Response {
+    let not_modified = if_none_match
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| etag_matches(value, etag));
+    let (status, body) = match not_modified {
+        true => {
+            headers.remove(header::CONTENT_TYPE);
+            headers.remove(header::CONTENT_LENGTH);
+            (StatusCode::NOT_MODIFIED, Body::empty())
+        }
+        false => (StatusCode::OK, body),
+    };
+    if let Ok(etag) = HeaderValue::from_str(etag) {
+        headers.insert(header::ETAG, etag);
+    }
+    if let Ok(cache_control) = HeaderValue::from_str(&format!("public, max-age={}", rule.max_age)) {
+        headers.insert(header::CACHE_CONTROL, cache_control);
+    }
+    let mut response = Response::new(boxed(body));
+    *response.status_mut() = status;
+    *response.headers_mut() = headers;
+    response
+}
+
+// the etag of the data, the meta of the response differs between calls.
+fn data_etag(body: &Value) -> String {
+    let data = serde_json::to_vec(body.get("data").unwrap_or(&Value::Null)).unwrap_or_default();
+    let digest = Sha256::digest(data);
+    format!("W/\"{}\"", hex::encode(&digest[..16]))
+}
+
+// `If-None-Match` is `*` or a list of etags, weak and strong etags match alike.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let strip = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    if_none_match
+        .split(',')
+        .any(|tag| tag.trim() == "*" || strip(tag) == strip(etag))
+}
+
+// report a cached body as cached, with its age and the latency of this request.
+fn mark_cached(body: &mut Value, age: Duration, latency: Duration) {
+    if let Some(meta) = body.get_mut("meta").and_then(Value::as_object_mut) {
+        meta.insert("cached".to_string(), Value::Bool(true));
+        meta.insert("cacheAgeMs".to_string(), (age.as_millis() as u64).into());
+        meta.insert("latencyMs".to_string(), (latency.as_millis() as u64).into());
+    }
+}
+
+// query params sorted, so the order of params does not split the cache.
+fn canonical_query(query: Option<&str>) -> String {
+    let mut params: Vec<&str> = query
+        .unwrap_or_default()
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .collect();
+    params.sort_unstable();
+    params.join("&")
+}
+
+fn query_network(query: Option<&str>) -> Option<NetworkType> {
+    query?
+        .split('&')
+        .find_map(|param| param.strip_prefix("chain_id="))
+        .and_then(|chain_id| chain_id.parse::<u64>().ok())
+        .and_then(NetworkType::from_chain_id)
+}
+
+async fn latest_block_number(network: NetworkType) -> Result<u64, Error> {
+    get_or_load(&LATEST_BLOCK_CACHES, network, async {
+        let client = match ProviderManager::instance().get_provider(network) {
+            Some(client) => client,
+            None => return Err("get provider failed".into()),
+        };
+        Ok(client.get_block_number().await?.as_u64())
+    })
+    .await
+}
+
+async fn read_body(mut body: axum::body::BoxBody) -> Result<Bytes, Error> {
+    let mut bytes = vec![];
+    while let Some(chunk) = body.data().await {
+        bytes.extend_from_slice(&chunk?);
+    }
+    Ok(Bytes::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::SocketAddr,
+        sync::atomic::{AtomicU64, Ordering},
+    };
+
+    use std::time::{Duration, Instant};
+
+    use axum::{http::header, middleware, routing::get, Json, Router};
+    use reqwest::StatusCode;
+    use serde_json::{json, Value};
+
+    use crate::{
+        apis::response::response_model::Response,
+        common::{
+            defines::NetworkType,
+            http_cache::{
+                canonical_query, data_etag, etag_matches, http_cache, invalidate_responses,
+                query_network, CachedResponse, RESPONSE_CACHES,
+            },
+            request_meta::track_request_meta,
+        },
+        models::ERC20Token,
+    };
+
+    #[test]
+    fn test_cache_key_and_etag() {
+        assert_eq!(
+            canonical_query(Some("public_address=0x1&chain_id=56&")),
+            "chain_id=56&public_address=0x1"
+        );
+        assert_eq!(canonical_query(None), "");
+        assert_eq!(
+            query_network(Some("public_address=0x1&chain_id=56")),
+            Some(NetworkType::BSCMainNetwork)
+        );
+        assert_eq!(query_network(Some("chain_id=2")), None);
+
+        // the etag is of the data, it does not change with the meta.
+        let etag =
+            data_etag(&json!({"status": 200, "data": {"amount": "1"}, "meta": {"latencyMs": 3}}));
+        assert_eq!(
+            etag,
+            data_etag(&json!({"status": 200, "data": {"amount": "1"}, "meta": {"latencyMs": 9}}))
+        );
+        assert_ne!(
+            etag,
+            data_etag(&json!({"status": 200, "data": {"amount": "2"}}))
+        );
+        assert!(etag.starts_with("W/\""));
+        assert!(etag_matches(&format!("\"other\", {}", etag), &etag));
+        assert!(etag_matches(&etag.replace("W/", ""), &etag));
+        assert!(etag_matches("*", &etag));
+        assert!(!etag_matches("\"other\"", &etag));
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_responses() {
+        let keys = [
+            "/v1/erc721/metadata/56/0xAbC/1?",
+            "/v1/erc721/metadata/56/0xabc/2?proxy_image=true",
+            "/v1/erc721/metadata/56/0xdef/1?",
+        ];
+        for key in keys {
+            RESPONSE_CACHES.lock().await.insert_with_ttl(
+                key.to_string(),
+                CachedResponse {
+                    body: Value::Null,
+                    etag: String::new(),
+                    cached_at: Instant::now(),
+                },
+                Duration::from_secs(60),
+            );
+        }
+
+        assert_eq!(
+            invalidate_responses("/v1/erc721/metadata/56/0xabc/1?").await,
+            1
+        );
+        assert_eq!(
+            invalidate_responses("/v1/erc721/metadata/56/0xabc/").await,
+            1
+        );
+        let remaining = RESPONSE_CACHES.lock().await.get(&keys[2].to_string());
+        assert!(remaining.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_http_cache() {
+        static CALLS: AtomicU64 = AtomicU64::new(0);
+        async fn prices() -> Json<Response<ERC20Token>> {
+            let calls = CALLS.fetch_add(1, Ordering::SeqCst) + 1;
+            Response::ok(ERC20Token {
+                amount: calls.to_string(),
+                ..Default::default()
+            })
+        }
+
+        let app = Router::new()
+            .route("/v1/prices", get(prices))
+            .layer(middleware::from_fn(track_request_meta))
+            .layer(middleware::from_fn(http_cache));
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr: SocketAddr = listener.local_addr().unwrap();
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+
+        let url = format!("http://{}/v1/prices?symbols=BNB", addr);
+        let client = reqwest::Client::new();
+        let response = client.get(&url).send().await.unwrap();
+        let etag = response.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, max-age=10"
+        );
+        let body: Value = response.json().await.unwrap();
+        assert_eq!(body["data"]["amount"], "1");
+        assert_eq!(body["meta"]["cached"], false);
+
+        // served from the cache without calling the handler.
+        let body: Value = client.get(&url).send().await.unwrap().json().await.unwrap();
+        assert_eq!(body["data"]["amount"], "1");
+        assert_eq!(body["meta"]["cached"], true);
+
+        let response = client
+            .get(&url)
+            .header(header::IF_NONE_MATCH, &etag)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag.as_str());
+
+        // revalidation by the client refreshes the cached result.
+        let body: Value = client
+            .get(&url)
+            .header(header::CACHE_CONTROL, "no-cache")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(body["data"]["amount"], "2");
+        assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,45 @@
+use ethers::{
+    abi::Token,
+    contract::builders::ContractCall,
+    prelude::Multicall,
+    providers::{Http, Provider},
+    types::U256,
+};
+
+use super::defines::{Error, MULTICALL_BATCH_SIZE};
+
+// unwrap the (success, value) tuple returned by multicall v2/v3.
+pub fn multicall_value(token: Token) -> Token {
+    match token {
+        Token::Tuple(mut values) if values.len() == 2 && matches!(values[0], Token::Bool(_)) => {
+            values.remove(1)
+        }
+        token => token,
+    }
+}
+
+// call a uint-returning view function for every argument, batched by multicall.
+pub async fn multicall_uints<F>(
+    multicall: &mut Multicall<Provider<Http>>,
+    args: &[U256],
+    build_call: F,
+) -> Result<Vec<U256>, Error>
+where
+    F: Fn(U256) -> ContractCall<Provider<Http>, U256>,
+{
+    let mut values = vec![];
+    for chunk in args.chunks(MULTICALL_BATCH_SIZE) {
+        multicall.clear_calls();
+        chunk.iter().for_each(|arg| {
+            multicall.add_call(build_call(*arg), false);
+        });
+        for token in multicall.call_raw().await? {
+            values.push(
+                multicall_value(token)
+                    .into_uint()
+                    .ok_or("invalid multicall result")?,
+            );
+        }
+    }
+    Ok(values)
+}
@@ -0,0 +1,230 @@
+use std::time::Duration;
+
+use ethers::{
+    prelude::{abigen, Lazy},
+    providers::{
+        ens::{namehash, reverse_address},
+        Http, Provider,
+    },
+    types::Address,
+};
+use tokio::sync::Mutex;
+
+use super::{
+    address::{check_address_count, parse_address, parse_public_addresses},
+    cache::{get_or_load, TtlCache},
+    defines::{
+        Error, NetworkType, ENS_REGISTRY_ADDRESS, NAME_CACHE_MAX_SIZE, NAME_CACHE_TTL_SECS,
+        NAME_NEGATIVE_CACHE_TTL_SECS, SPACE_ID_REGISTRY_ADDRESS,
+    },
+    provider::ProviderManager,
+    tasks::spawn_all,
+};
+
+abigen!(NameRegistryContract, "./src/abi/name_registry.json");
+abigen!(NameResolverContract, "./src/abi/name_resolver.json");
+
+// network of the registry and the name, or the address of a primary name lookup.
+type NameKey = (NetworkType, String);
+type PrimaryNameKey = (NetworkType, Address);
+
+static NAME_CACHES: Lazy<Mutex<TtlCache<NameKey, Address>>> =
+    Lazy::new(|| Mutex::new(new_name_cache()));
+static PRIMARY_NAME_CACHES: Lazy<Mutex<TtlCache<PrimaryNameKey, Option<String>>>> =
+    Lazy::new(|| Mutex::new(new_name_cache()));
+
+fn new_name_cache<K: Eq + std::hash::Hash + Clone, V: Clone>() -> TtlCache<K, V> {
+    TtlCache::new(
+        Duration::from_secs(NAME_CACHE_TTL_SECS),
+        Duration::from_secs(NAME_NEGATIVE_CACHE_TTL_SECS),
+        NAME_CACHE_MAX_SIZE,
+    )
+}
+
+// `.eth` names are ENS names, `.bnb` names are Space ID names.
+pub fn is_name(input: &str) -> bool {
+    let input = input.trim().to_lowercase();
+    input.ends_with(".eth") || input.ends_with(".bnb")
+}
+
+// get the network and registry a name is resolved on. ENS names are resolved on
+// goerli for goerli requests and on ethereum mainnet otherwise.
+fn name_registry(network: NetworkType, name: &str) -> Option<(NetworkType, &'static str)> {
+    if name.ends_with(".eth") {
+        return match network {
+            NetworkType::GoerliTestnet => Some((network, ENS_REGISTRY_ADDRESS)),
+            _ => Some((NetworkType::EthereumMainnet, ENS_REGISTRY_ADDRESS)),
+        };
+    }
+    if name.ends_with(".bnb") {
+        return Some((NetworkType::BSCMainNetwork, SPACE_ID_REGISTRY_ADDRESS));
+    }
+    None
+}
+
+// get the network and registry the primary name of an address on the network is looked up on.
+fn reverse_registry(network: NetworkType) -> (NetworkType, &'static str) {
+    match network {
+        NetworkType::EthereumMainnet | NetworkType::GoerliTestnet => {
+            (network, ENS_REGISTRY_ADDRESS)
+        }
+        NetworkType::BSCMainNetwork | NetworkType::BSCTestNetwork => {
+            (NetworkType::BSCMainNetwork, SPACE_ID_REGISTRY_ADDRESS)
+        }
+    }
+}
+
+// parse an address, or resolve an ENS or Space ID name to its address.
+pub async fn resolve_address(network: NetworkType, input: &str) -> Result<Address, Error> {
+    let input = input.trim();
+    let name = input.to_lowercase();
+    let (registry_network, registry) = match name_registry(network, &name) {
+        Some(registry) => registry,
+        None => return parse_address(input),
+    };
+    get_or_load(&NAME_CACHES, (registry_network, name.clone()), async {
+        let address = resolve_name(registry_network, registry, &name).await?;
+        if address.is_zero() {
+            return Err("name has no address".into());
+        }
+        Ok(address)
+    })
+    .await
+    .map_err(|err| format!("name is not resolved: {}: {}", name, err).into())
+}
+
+// resolve addresses or names of a multi-address request, duplicates are dropped.
+pub async fn resolve_public_addresses(
+    network: NetworkType,
+    public_addresses: &[String],
+) -> Result<Vec<Address>, Error> {
+    if !public_addresses.iter().any(|input| is_name(input)) {
+        return parse_public_addresses(public_addresses);
+    }
+    check_address_count(public_addresses.len())?;
+    let results = spawn_all(public_addresses.iter().map(|input| {
+        let input = input.clone();
+        async move { resolve_address(network, &input).await }
+    }))
+    .await;
+    let mut addresses: Vec<Address> = vec![];
+    for result in results {
+        let address = result?;
+        if !addresses.contains(&address) {
+            addresses.push(address);
+        }
+    }
+    Ok(addresses)
+}
+
+// get the primary name of an address, only when it resolves back to the address.
+pub async fn lookup_primary_name(network: NetworkType, address: Address) -> Option<String> {
+    let (registry_network, registry) = reverse_registry(network);
+    get_or_load(&PRIMARY_NAME_CACHES, (registry_network, address), async {
+        let name = lookup_name(registry_network, registry, address).await?;
+        match name {
+            Some(name) if resolve_address(registry_network, &name).await.ok() == Some(address) => {
+                Ok(Some(name))
+            }
+            _ => Ok(None),
+        }
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+// get the primary ENS name of an address, or its Space ID name when it has none.
+pub async fn lookup_any_primary_name(address: Address) -> Option<String> {
+    match lookup_primary_name(NetworkType::EthereumMainnet, address).await {
+        Some(name) => Some(name),
+        None => lookup_primary_name(NetworkType::BSCMainNetwork, address).await,
+    }
+}
+
+async fn get_resolver(
+    network: NetworkType,
+    registry: &str,
+    name: &str,
+) -> Result<Option<NameResolverContract<Provider<Http>>>, Error> {
+    let client = match ProviderManager::instance().get_provider(network) {
+        Some(client) => client,
+        None => return Err(format!("name resolution is not available on {}", network).into()),
+    };
+    let registry = NameRegistryContract::new(registry.parse::<Address>()?, client.clone());
+    let resolver = registry.resolver(namehash(name).into()).call().await?;
+    if resolver.is_zero() {
+        return Ok(None);
+    }
+    Ok(Some(NameResolverContract::new(resolver, client)))
+}
+
+async fn resolve_name(network: NetworkType, registry: &str, name: &str) -> Result<Address, Error> {
+    match get_resolver(network, registry, name).await? {
+        Some(resolver) => Ok(resolver.addr(namehash(name).into()).call().await?),
+        None => Err("name is not registered".into()),
+    }
+}
+
+async fn lookup_name(
+    network: NetworkType,
+    registry: &str,
+    address: Address,
+) -> Result<Option<String>, Error> {
+    let reverse_name = reverse_address(address);
+    let resolver = match get_resolver(network, registry, &reverse_name).await? {
+        Some(resolver) => resolver,
+        None => return Ok(None),
+    };
+    let name = resolver.name(namehash(&reverse_name).into()).call().await?;
+    Ok(Some(name).filter(|name| !name.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{
+        defines::{NetworkType, ENS_REGISTRY_ADDRESS, SPACE_ID_REGISTRY_ADDRESS},
+        name_resolver::{is_name, name_registry, resolve_address},
+    };
+
+    #[test]
+    fn test_name_registry() {
+        assert!(is_name("Vitalik.ETH"));
+        assert!(is_name("namiland.bnb"));
+        assert!(!is_name("0x5FaB721a3fa13c0219EB24C121f9F6482f64f274"));
+
+        assert_eq!(
+            name_registry(NetworkType::BSCMainNetwork, "vitalik.eth"),
+            Some((NetworkType::EthereumMainnet, ENS_REGISTRY_ADDRESS))
+        );
+        assert_eq!(
+            name_registry(NetworkType::GoerliTestnet, "vitalik.eth"),
+            Some((NetworkType::GoerliTestnet, ENS_REGISTRY_ADDRESS))
+        );
+        assert_eq!(
+            name_registry(NetworkType::EthereumMainnet, "namiland.bnb"),
+            Some((NetworkType::BSCMainNetwork, SPACE_ID_REGISTRY_ADDRESS))
+        );
+        assert_eq!(
+            name_registry(NetworkType::EthereumMainnet, "namiland"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_address_without_name() {
+        let address = resolve_address(
+            NetworkType::GoerliTestnet,
+            " 0x5FaB721a3fa13c0219EB24C121f9F6482f64f274 ",
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            format!("{:?}", address),
+            "0x5fab721a3fa13c0219eb24c121f9f6482f64f274"
+        );
+        assert!(resolve_address(NetworkType::GoerliTestnet, "namiland")
+            .await
+            .is_err());
+    }
+}
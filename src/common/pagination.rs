@@ -0,0 +1,47 @@
+use std::cmp::Ordering;
+
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+pub const MAX_PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    // parse sort order from query string, default is descending.
+    pub fn parse(value: Option<&str>) -> Option<SortOrder> {
+        match value {
+            None => Some(SortOrder::Desc),
+            Some("asc") => Some(SortOrder::Asc),
+            Some("desc") => Some(SortOrder::Desc),
+            Some(_) => None,
+        }
+    }
+
+    pub fn apply(&self, ordering: Ordering) -> Ordering {
+        match self {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    }
+}
+
+// normalize page (1-based) and page size from query string.
+pub fn page_params(page: Option<usize>, page_size: Option<usize>) -> (usize, usize) {
+    let page = page.unwrap_or(1).max(1);
+    let page_size = page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    (page, page_size)
+}
+
+// take one page out of the items.
+pub fn paginate<T>(items: Vec<T>, page: usize, page_size: usize) -> Vec<T> {
+    items
+        .into_iter()
+        .skip((page - 1) * page_size)
+        .take(page_size)
+        .collect()
+}
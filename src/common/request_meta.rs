@@ -0,0 +1,165 @@
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{body::Body, http::Request, middleware::Next, response::IntoResponse};
+
+use super::defines::NetworkType;
+use crate::models::ResponseMeta;
+
+// What a request read, collected while it is handled and reported in the `meta` of
+// its response.
+#[derive(Debug)]
+struct RequestMeta {
+    started_at: Instant,
+    network: Option<NetworkType>,
+    // reads of a request spanning networks, e.g. the wallet inventory, have no network.
+    multiple_networks: bool,
+    block_number: Option<u64>,
+    block_hash: Option<String>,
+    cached: bool,
+    // age of the oldest cached value the data was built from.
+    cache_age: Option<Duration>,
+}
+
+type SharedRequestMeta = Arc<Mutex<RequestMeta>>;
+
+impl RequestMeta {
+    fn shared() -> SharedRequestMeta {
+        Arc::new(Mutex::new(RequestMeta {
+            started_at: Instant::now(),
+            network: None,
+            multiple_networks: false,
+            block_number: None,
+            block_hash: None,
+            cached: false,
+            cache_age: None,
+        }))
+    }
+}
+
+tokio::task_local! {
+    static REQUEST_META: SharedRequestMeta;
+}
+
+// collect the meta of the requests handled by the router.
+pub async fn track_request_meta(request: Request<Body>, next: Next<Body>) -> impl IntoResponse {
+    REQUEST_META
+        .scope(RequestMeta::shared(), next.run(request))
+        .await
+}
+
+// run a spawned task in the request meta of the spawning task, if any.
+pub fn inherit<F: Future>(future: F) -> impl Future<Output = F::Output> {
+    let meta = REQUEST_META.try_with(|meta| meta.clone()).ok();
+    async move {
+        match meta {
+            Some(meta) => REQUEST_META.scope(meta, future).await,
+            None => future.await,
+        }
+    }
+}
+
+// records outside of a request, e.g. in background workers, are ignored.
+fn record<F: FnOnce(&mut RequestMeta)>(update: F) {
+    let _ = REQUEST_META.try_with(|meta| {
+        if let Ok(mut meta) = meta.lock() {
+            update(&mut meta);
+        }
+    });
+}
+
+pub fn record_network(network: NetworkType) {
+    record(|meta| match meta.network {
+        Some(recorded) if recorded != network => meta.multiple_networks = true,
+        Some(_) => {}
+        None => meta.network = Some(network),
+    });
+}
+
+pub fn record_block(block_number: u64, block_hash: Option<String>) {
+    record(|meta| {
+        meta.block_number = Some(block_number);
+        meta.block_hash = block_hash;
+    });
+}
+
+pub fn record_cache_hit(age: Duration) {
+    record(|meta| {
+        meta.cached = true;
+        meta.cache_age = Some(meta.cache_age.map_or(age, |oldest| oldest.max(age)));
+    });
+}
+
+// the meta of the current request, none outside of a request.
+pub fn response_meta() -> Option<ResponseMeta> {
+    REQUEST_META
+        .try_with(|meta| {
+            let meta = meta.lock().ok()?;
+            let network = meta.network.filter(|_| !meta.multiple_networks);
+            Some(ResponseMeta {
+                network,
+                chain_id: network.map(|network| network.chain_id()),
+                block_number: meta.block_number,
+                block_hash: meta.block_hash.clone(),
+                cached: meta.cached,
+                cache_age_ms: meta.cache_age.map(|age| age.as_millis() as u64),
+                latency_ms: meta.started_at.elapsed().as_millis() as u64,
+            })
+        })
+        .ok()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::common::{
+        defines::NetworkType,
+        request_meta::{
+            inherit, record_block, record_cache_hit, record_network, response_meta, RequestMeta,
+            REQUEST_META,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_response_meta() {
+        assert!(response_meta().is_none());
+
+        let meta = REQUEST_META
+            .scope(RequestMeta::shared(), async {
+                record_network(NetworkType::BSCMainNetwork);
+                record_block(42, Some("0x2a".to_string()));
+                // records of spawned tasks are kept when they inherit the meta.
+                tokio::spawn(inherit(async {
+                    record_cache_hit(Duration::from_millis(30));
+                    record_cache_hit(Duration::from_millis(10));
+                }))
+                .await
+                .unwrap();
+                response_meta()
+            })
+            .await
+            .unwrap();
+        assert_eq!(meta.network, Some(NetworkType::BSCMainNetwork));
+        assert_eq!(meta.chain_id, Some(NetworkType::BSCMainNetwork.chain_id()));
+        assert_eq!(meta.block_number, Some(42));
+        assert_eq!(meta.block_hash.as_deref(), Some("0x2a"));
+        assert!(meta.cached);
+        assert_eq!(meta.cache_age_ms, Some(30));
+
+        let meta = REQUEST_META
+            .scope(RequestMeta::shared(), async {
+                record_network(NetworkType::BSCMainNetwork);
+                record_network(NetworkType::EthereumMainnet);
+                response_meta()
+            })
+            .await
+            .unwrap();
+        assert_eq!(meta.network, None);
+        assert!(!meta.cached);
+    }
+}
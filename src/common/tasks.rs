@@ -0,0 +1,20 @@
+use super::{defines::Error, request_meta::inherit};
+
+// run the queries concurrently, errors are reported as messages. Queries share the
+// request meta of the caller.
+pub async fn spawn_all<T, F, I>(queries: I) -> Vec<Result<T, String>>
+where
+    T: Send + 'static,
+    F: std::future::Future<Output = Result<T, Error>> + Send + 'static,
+    I: Iterator<Item = F>,
+{
+    let handles: Vec<_> = queries.map(|query| tokio::spawn(inherit(query))).collect();
+    let mut results = vec![];
+    for handle in handles {
+        results.push(match handle.await {
+            Ok(result) => result.map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string()),
+        });
+    }
+    results
+}
@@ -0,0 +1,177 @@
+use std::env;
+
+use ethers::types::U256;
+
+use super::defines::{
+    Error, DEFAULT_ARWEAVE_GATEWAYS, DEFAULT_IPFS_GATEWAYS, NFT_METADATA_MAX_BYTES,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ResolvedUri {
+    // http urls to try in order, the first one which succeeds wins.
+    Http(Vec<String>),
+    // content embedded in a `data:` uri.
+    Inline(Vec<u8>),
+}
+
+// replace the `{id}` placeholder with the lowercase, 64 hex chars nft id as EIP-1155 defined.
+pub fn substitute_id(uri: &str, nft_id: &U256) -> String {
+    uri.replace("{id}", &format!("{:0>64}", format!("{:x}", nft_id)))
+}
+
+// map `ipfs://`, `ar://` and `data:` uris to http urls or inline content.
+pub fn resolve_uri(uri: &str) -> Result<ResolvedUri, Error> {
+    let uri = uri.trim();
+    if let Some(path) = uri.strip_prefix("ipfs://") {
+        let path = path.trim_start_matches("ipfs/");
+        return Ok(ResolvedUri::Http(
+            gateways("IPFS_GATEWAYS", &DEFAULT_IPFS_GATEWAYS)
+                .iter()
+                .map(|gateway| format!("{}/ipfs/{}", gateway, path))
+                .collect(),
+        ));
+    }
+    if let Some(path) = uri.strip_prefix("ar://") {
+        return Ok(ResolvedUri::Http(
+            gateways("ARWEAVE_GATEWAYS", &DEFAULT_ARWEAVE_GATEWAYS)
+                .iter()
+                .map(|gateway| format!("{}/{}", gateway, path))
+                .collect(),
+        ));
+    }
+    if let Some(data) = uri.strip_prefix("data:") {
+        let (media_type, content) = data.split_once(',').ok_or("data uri is invalid")?;
+        let content = if media_type.ends_with(";base64") {
+            base64::decode(content)?
+        } else {
+            percent_decode(content)?
+        };
+        return Ok(ResolvedUri::Inline(content));
+    }
+    if uri.starts_with("http://") || uri.starts_with("https://") {
+        return Ok(ResolvedUri::Http(vec![uri.to_string()]));
+    }
+
+    Err(format!("uri scheme is not supported: {}", uri).into())
+}
+
+// fetch the content of an uri as text.
+pub async fn fetch_uri_content(uri: &str) -> Result<String, Error> {
+    Ok(String::from_utf8(
+        fetch_uri_bytes(uri, NFT_METADATA_MAX_BYTES).await?,
+    )?)
+}
+
+// Fetch the content of an uri, falling back to the next gateway on failure. Content
+// larger than `max_bytes` is rejected without downloading the rest of it.
+pub async fn fetch_uri_bytes(uri: &str, max_bytes: usize) -> Result<Vec<u8>, Error> {
+    let too_large = || format!("content of {} is larger than {} bytes", uri, max_bytes);
+    let urls = match resolve_uri(uri)? {
+        ResolvedUri::Inline(content) if content.len() > max_bytes => return Err(too_large().into()),
+        ResolvedUri::Inline(content) => return Ok(content),
+        ResolvedUri::Http(urls) => urls,
+    };
+
+    let requester = reqwest::Client::new();
+    let mut last_error: Error = "no gateway is configured".into();
+    for url in urls {
+        let result = async {
+            let mut response = requester.get(&url).send().await?.error_for_status()?;
+            if response.content_length().unwrap_or(0) > max_bytes as u64 {
+                return Err(too_large().into());
+            }
+            // the content length is not always sent, the chunks are counted as well.
+            let mut content = vec![];
+            while let Some(chunk) = response.chunk().await? {
+                if content.len() + chunk.len() > max_bytes {
+                    return Err(too_large().into());
+                }
+                content.extend_from_slice(&chunk);
+            }
+            Ok::<Vec<u8>, Error>(content)
+        }
+        .await;
+        match result {
+            Ok(content) => return Ok(content),
+            Err(err) => last_error = err,
+        }
+    }
+    Err(last_error)
+}
+
+// gateways from a comma separated env, or the default ones.
+fn gateways(env_name: &str, defaults: &[&str]) -> Vec<String> {
+    let configured: Vec<String> = env::var(env_name)
+        .unwrap_or_default()
+        .split(',')
+        .map(|gateway| gateway.trim().trim_end_matches('/').to_string())
+        .filter(|gateway| !gateway.is_empty())
+        .collect();
+    match configured.is_empty() {
+        true => defaults.iter().map(|gateway| gateway.to_string()).collect(),
+        false => configured,
+    }
+}
+
+fn percent_decode(content: &str) -> Result<Vec<u8>, Error> {
+    let bytes = content.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' && index + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[index + 1..index + 3])?;
+            decoded.push(u8::from_str_radix(hex, 16)?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::U256;
+
+    use crate::common::uri::{resolve_uri, substitute_id, ResolvedUri};
+
+    #[test]
+    fn test_substitute_id() {
+        assert_eq!(
+            substitute_id("https://example.com/{id}.json", &U256::from(10001)),
+            "https://example.com/0000000000000000000000000000000000000000000000000000000000002711.json"
+        );
+        assert_eq!(
+            substitute_id("https://example.com/10001", &U256::from(10001)),
+            "https://example.com/10001"
+        );
+    }
+
+    #[test]
+    fn test_resolve_gateway_uri() {
+        match resolve_uri("ipfs://ipfs/QmHash/1.json").unwrap() {
+            ResolvedUri::Http(urls) => assert!(urls
+                .iter()
+                .all(|url| url.ends_with("/ipfs/QmHash/1.json") && url.starts_with("https://"))),
+            _ => panic!("ipfs uri should resolve to gateway urls"),
+        }
+        match resolve_uri("ar://TxId").unwrap() {
+            ResolvedUri::Http(urls) => assert!(urls.iter().all(|url| url.ends_with("/TxId"))),
+            _ => panic!("arweave uri should resolve to gateway urls"),
+        }
+        assert!(resolve_uri("ftp://host/1.json").is_err());
+    }
+
+    #[test]
+    fn test_resolve_data_uri() {
+        assert_eq!(
+            resolve_uri("data:application/json;base64,eyJuYW1lIjoicm9kIn0=").unwrap(),
+            ResolvedUri::Inline(br#"{"name":"rod"}"#.to_vec())
+        );
+        assert_eq!(
+            resolve_uri("data:application/json,%7B%22name%22%3A%22rod%22%7D").unwrap(),
+            ResolvedUri::Inline(br#"{"name":"rod"}"#.to_vec())
+        );
+    }
+}
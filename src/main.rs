@@ -14,6 +14,7 @@ mod common;
 mod models;
 mod router;
 mod services;
+mod workers;
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
@@ -35,6 +36,12 @@ async fn main() -> Result<(), Error> {
     ProviderManager::instance().set_provider(NetworkType::BSCMainNetwork, bsc_main_client);
     ProviderManager::instance().set_provider(NetworkType::BSCTestNetwork, bsc_test_client);
 
+    common::config::load_config()?;
+
+    tokio::spawn(workers::metadata_refresher::refresh_metadata());
+    tokio::spawn(workers::uri_watcher::watch_uri_changes());
+    tokio::spawn(workers::webhook_dispatcher::dispatch_webhooks());
+
     let app = router::new_router();
     let addr = SocketAddr::from(([0, 0, 0, 0], 8080));
     info!("web server is listening on {}", addr);
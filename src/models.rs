@@ -1,4 +1,7 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use utoipa::ToSchema;
 
 use crate::common::defines::NetworkType;
@@ -8,21 +11,42 @@ pub struct ERC20Token {
     pub symbol: String,
     pub decimal: u8,
     pub amount: String,
+    // only present when usd values are requested and the token has a price pair.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usd_price: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usd_value: Option<f64>,
+    // block the state was read at, the latest block unless a `finality` is requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NamiXStakedInfo {
     pub public_address: String,
+    // reverse resolved ENS or Space ID name of the address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_name: Option<String>,
+    // whether the address is a contract rather than an externally owned account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_contract: Option<bool>,
     pub staked_amount: String,
     pub staked_time: String,
+    // block the state was read at, the latest block unless a `finality` is requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all(deserialize = "snake_case", serialize = "camelCase"))]
 pub struct NFTTrait {
     pub trait_type: String,
-    pub value: String,
+    // string, number or boolean.
+    #[schema(value_type = Object)]
+    pub value: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_type: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
@@ -36,6 +60,20 @@ pub struct NamiLandERC1155NFTMetadata {
     pub image: String,
     pub external_url: String,
     pub attributes: Vec<NFTTrait>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub animation_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Object)]
+    pub properties: Option<Value>,
+    // unknown fields of the metadata are kept as they are.
+    #[serde(flatten)]
+    #[schema(value_type = Object)]
+    pub extra: HashMap<String, Value>,
+    // problems found while parsing the metadata, which did not fail the request.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
@@ -43,6 +81,8 @@ pub struct NamiLandERC1155NFTMetadata {
 pub struct OwnershipItem {
     pub nft_id: String,
     pub amount: u64,
+    // locked nft can not be transferred or listed on marketplace.
+    pub locked: bool,
     pub nft_metadata: NamiLandERC1155NFTMetadata,
 }
 
@@ -50,14 +90,583 @@ pub struct OwnershipItem {
 #[serde(rename_all = "camelCase")]
 pub struct NamiLandNFTOwnership {
     pub public_address: String,
+    // reverse resolved ENS or Space ID name of the address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_name: Option<String>,
+    // whether the address is a contract rather than an externally owned account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_contract: Option<bool>,
     pub network: NetworkType,
     pub contract_address: String,
     pub ownerships: Vec<OwnershipItem>,
+    // block the state was read at, the latest block unless a `finality` is requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressFailure {
+    pub public_address: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NFTAmountTotal {
+    pub nft_id: String,
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NamiLandNFTOwnershipBatch {
+    pub network: NetworkType,
+    pub contract_address: String,
+    pub ownerships: Vec<NamiLandNFTOwnership>,
+    // amount of every nft id summed across all addresses.
+    pub totals: Vec<NFTAmountTotal>,
+    pub failures: Vec<AddressFailure>,
+    // block the state was read at, the latest block unless a `finality` is requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ERC20BalanceItem {
+    pub public_address: String,
+    // reverse resolved ENS or Space ID name of the address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_name: Option<String>,
+    // whether the address is a contract rather than an externally owned account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_contract: Option<bool>,
+    pub amount: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usd_value: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ERC20BalanceBatch {
+    pub symbol: String,
+    pub decimal: u8,
+    pub balances: Vec<ERC20BalanceItem>,
+    pub total_amount: String,
+    // only present when usd values are requested and the token has a price pair.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usd_price: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_usd_value: Option<f64>,
+    pub failures: Vec<AddressFailure>,
+    // block the state was read at, the latest block unless a `finality` is requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LPPosition {
+    pub public_address: String,
+    pub network: NetworkType,
+    pub pair_id: String,
+    pub pair_address: String,
+    // lp token balance of the address.
+    pub lp_token: ERC20Token,
+    pub total_supply: String,
+    // share of the pool owned by the address, from 0 to 1.
+    pub share_of_pool: f64,
+    // amounts of token0 and token1 of the pair redeemable by the lp token balance.
+    pub underlying_tokens: Vec<ERC20Token>,
+    // usd value of the underlying tokens with a price, when usd values are requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_usd_value: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPrice {
+    pub symbol: String,
+    // network of the pair the price is derived from.
+    pub network: NetworkType,
+    pub pair_address: String,
+    pub usd_price: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPrices {
+    pub prices: Vec<TokenPrice>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionStatusType {
+    #[default]
+    NotFound,
+    Pending,
+    Confirmed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionEventParam {
+    pub name: String,
+    // addresses are checksummed, integers are decimal strings and bytes are hex.
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionEvent {
+    pub log_index: Option<u64>,
+    pub contract_address: String,
+    // abi the log is decoded with: erc20, erc721, namiland_erc1155 or neco_stake.
+    pub contract_type: String,
+    pub event: String,
+    pub params: Vec<TransactionEventParam>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionStatus {
+    pub tx_hash: String,
+    pub network: NetworkType,
+    pub status: TransactionStatusType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    // blocks since the block of the transaction, including it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmations: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effective_gas_price: Option<String>,
+    // logs decoded with the known abis, logs of other contracts are skipped.
+    pub events: Vec<TransactionEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetDelta {
+    // nft id of an erc1155 change, empty for erc20 changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nft_id: Option<String>,
+    // signed decimal amount, negative when sent by the address.
+    pub amount: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletAssetChange {
+    pub public_address: String,
+    pub network: NetworkType,
+    pub block_number: u64,
+    pub tx_hash: String,
+    pub log_index: u64,
+    pub contract_address: String,
+    // erc20, erc1155 or stake.
+    pub asset_type: String,
+    pub deltas: Vec<AssetDelta>,
+    // current erc20 balance of the address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<ERC20Token>,
+    // current staked info of the address, for transfers to or from the stake contract.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub staked: Option<NamiXStakedInfo>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventType {
+    // `Transfer` events of an erc20 token.
+    #[default]
+    Erc20Transfer,
+    // `TransferSingle` and `TransferBatch` events of an erc1155 collection.
+    Erc1155Transfer,
+    // transfers of the staked token to or from the NECO stake contract.
+    Stake,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscription {
+    pub id: String,
+    pub url: String,
+    pub network: NetworkType,
+    pub contract_address: String,
+    pub event_type: WebhookEventType,
+    // only events sent or received by the addresses are delivered, all events when empty.
+    pub public_addresses: Vec<String>,
+    // blocks mined on top of the event block before it is delivered.
+    pub confirmations: u64,
+    // next block scanned for events.
+    pub next_block: u64,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscriptions {
+    pub subscriptions: Vec<WebhookSubscription>,
+}
+
+// json body posted to the webhook url.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    // unique per subscription and log, receivers can use it to drop duplicates.
+    pub delivery_id: String,
+    pub subscription_id: String,
+    pub event_type: WebhookEventType,
+    pub network: NetworkType,
+    pub contract_address: String,
+    pub block_number: u64,
+    pub tx_hash: String,
+    pub log_index: u64,
+    pub from: String,
+    pub to: String,
+    // nft ids of erc1155 transfers, empty for erc20 transfers.
+    pub nft_ids: Vec<String>,
+    pub amounts: Vec<String>,
+    // stake or unstake, for stake events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    #[default]
+    Pending,
+    Delivered,
+    // all attempts failed.
+    Failed,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub subscription_id: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+    // unix time of the next attempt of a pending delivery.
+    pub next_attempt_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_status_code: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub created_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<u64>,
+    pub payload: WebhookPayload,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveries {
+    pub subscription_id: String,
+    // newest first.
+    pub deliveries: Vec<WebhookDelivery>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletStreamBlock {
+    pub network: NetworkType,
+    // last block scanned, the stream resumes after it with `Last-Event-ID`.
+    pub block_number: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NamiXStakedInfoBatch {
+    pub stakes: Vec<NamiXStakedInfo>,
+    pub total_staked_amount: String,
+    pub failures: Vec<AddressFailure>,
+    // block the state was read at, the latest block unless a `finality` is requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NFTMetadataItem {
+    pub nft_id: String,
+    pub metadata: NamiLandERC1155NFTMetadata,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NFTMetadataFailure {
+    pub nft_id: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NamiLandNFTMetadataBatch {
+    pub network: NetworkType,
+    pub items: Vec<NFTMetadataItem>,
+    pub failures: Vec<NFTMetadataFailure>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ERC721Ownership {
+    pub public_address: String,
+    // reverse resolved ENS or Space ID name of the address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_name: Option<String>,
+    // whether the address is a contract rather than an externally owned account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_contract: Option<bool>,
+    pub network: NetworkType,
+    pub contract_address: String,
+    pub name: String,
+    pub symbol: String,
+    // tokens are enumerated on chain when the contract supports ERC721Enumerable,
+    // otherwise they are found from transfer events.
+    pub enumerable: bool,
+    pub balance: u64,
+    pub tokens: Vec<NFTMetadataItem>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ERC721TokenOwner {
+    pub network: NetworkType,
+    pub contract_address: String,
+    pub token_id: String,
+    pub owner: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkInventory {
+    pub network: NetworkType,
+    pub chain_id: u64,
+    // "ok", "partial" when some queries failed, or "error" when all of them failed.
+    pub status: String,
+    pub errors: Vec<String>,
+    pub elapsed_ms: u64,
+    // whether the address is a contract on this network.
+    pub is_contract: Option<bool>,
+    pub native: Option<ERC20Token>,
+    pub erc20_tokens: Vec<ERC20Token>,
+    pub erc1155_ownerships: Vec<NamiLandNFTOwnership>,
+    pub erc721_ownerships: Vec<ERC721Ownership>,
+    pub stake: Option<NamiXStakedInfo>,
+    // usd value of native and erc20 balances with a price, when usd values are requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_usd_value: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletInventory {
+    pub public_address: String,
+    // reverse resolved ENS or Space ID name of the address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_name: Option<String>,
+    pub elapsed_ms: u64,
+    pub networks: Vec<NetworkInventory>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_usd_value: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NamiLandNFTCatalogue {
+    pub network: NetworkType,
+    pub contract_address: String,
+    // number of items matching the filters, before pagination.
+    pub total: u64,
+    pub items: Vec<NFTMetadataItem>,
+    pub page: u64,
+    pub page_size: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetValue {
+    pub value: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TraitFacet {
+    pub trait_type: String,
+    pub values: Vec<FacetValue>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NamiLandNFTFacets {
+    pub network: NetworkType,
+    // number of items matching the filters, the facets count these items only.
+    pub total: u64,
+    pub nft_type1: Vec<FacetValue>,
+    pub nft_type2: Vec<FacetValue>,
+    pub traits: Vec<TraitFacet>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NamiLandLockedNFTs {
+    pub network: NetworkType,
+    pub contract_address: String,
+    pub nft_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NamiLandNFTPermission {
+    pub public_address: String,
+    // reverse resolved ENS or Space ID name of the address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_name: Option<String>,
+    // whether the address is a contract rather than an externally owned account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_contract: Option<bool>,
+    pub network: NetworkType,
+    pub contract_address: String,
+    pub is_owner: bool,
+    pub is_minter: bool,
+    pub is_transfer_whitelisted: bool,
+    // only present when an operator is given.
+    pub operator: Option<String>,
+    pub is_approved_for_all: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionToken {
+    pub nft_id: String,
+    pub total_supply: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NamiLandNFTCollection {
+    pub network: NetworkType,
+    pub contract_address: String,
+    pub name: String,
+    pub symbol: String,
+    pub base_token_uri: String,
+    pub owner: String,
+    pub tokens: Vec<CollectionToken>,
+}
+
+// Collection metadata in the shape of OpenSea `contractURI`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ContractURIMetadata {
+    pub name: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_link: Option<String>,
+    pub seller_fee_basis_points: u32,
+    pub fee_recipient: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NFTHolder {
+    pub public_address: String,
+    pub amount: String,
+    // number of distinct nft ids held by this address.
+    pub nft_id_count: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NFTSupply {
+    pub nft_id: String,
+    pub total_supply: String,
+    pub holder_count: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NamiLandNFTHolders {
+    pub network: NetworkType,
+    pub contract_address: String,
+    // holders of this nft id only, or of the whole collection when it is empty.
+    pub nft_id: Option<String>,
+    pub unique_holders: u64,
+    pub supplies: Vec<NFTSupply>,
+    pub holders: Vec<NFTHolder>,
+    pub page: u64,
+    pub page_size: u64,
+    pub scanned_block: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataRefreshStatus {
+    pub network: NetworkType,
+    pub collection: String,
+    // unix timestamps in seconds.
+    pub last_started_at: u64,
+    pub last_finished_at: Option<u64>,
+    pub duration_ms: Option<u64>,
+    pub refreshed: u64,
+    pub failed: u64,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthStatus {
+    // "ok", "starting" before the first refresh finished, or "degraded" when it failed.
+    pub status: String,
+    pub metadata_refresh: Vec<MetadataRefreshStatus>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CachePurgeResult {
+    pub purged_entries: u64,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
 pub struct EmptyData {}
 
+// What the data of a successful response was read at, for reasoning about freshness.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseMeta {
+    // only present when the data was read from one network.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u64>,
+    // block the state was read at, the latest block unless a `finality` is requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<String>,
+    // whether any part of the data came from a cache, with the age of the oldest entry.
+    pub cached: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_age_ms: Option<u64>,
+    // time spent handling the request on the server.
+    pub latency_ms: u64,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AddressConfig {
     pub bsc_test: Address,
@@ -1,41 +1,161 @@
 use std::collections::HashMap;
 use std::env;
 
-use axum::{routing::get, Router};
+use axum::{
+    middleware,
+    routing::{delete, get, post},
+    Router,
+};
 use tower_http::cors::{Any, CorsLayer};
 use utoipa::openapi::Server;
 use utoipa::{Modify, OpenApi};
 use utoipa_swagger_ui::SwaggerUi;
 
-use crate::apis::v1;
+use crate::apis::{
+    request::request_model::{
+        GetERC20BalanceBatchRequest, GetNECOStakedInfoBatchRequest, GetNFTMetadataBatchRequest,
+        GetNFTOwnershipBatchRequest, RegisterWebhookRequest,
+    },
+    v1,
+};
+use crate::common::{http_cache::http_cache, request_meta::track_request_meta};
 use crate::{
     apis::response::response_model::{
-        ERC1155MetadataResponse, ERC1155OwnershipResponse, ERC20TokenResponse, ErrorResponse,
-        NECOStakedInfoResponse,
+        CachePurgeResponse, ERC1155CatalogueResponse, ERC1155CollectionResponse,
+        ERC1155FacetsResponse, ERC1155HoldersResponse, ERC1155LockedResponse,
+        ERC1155MetadataBatchResponse, ERC1155MetadataResponse, ERC1155OwnershipBatchResponse,
+        ERC1155OwnershipResponse, ERC1155PermissionResponse, ERC20BalanceBatchResponse,
+        ERC20TokenResponse, ERC721MetadataResponse, ERC721OwnerResponse, ERC721OwnershipResponse,
+        ErrorResponse, HealthResponse, LPPositionResponse, NECOStakedInfoBatchResponse,
+        NECOStakedInfoResponse, TokenPricesResponse, TransactionStatusResponse,
+        WalletInventoryResponse, WebhookDeliveriesResponse, WebhookSubscriptionResponse,
+        WebhookSubscriptionsResponse,
     },
     common::defines::NetworkType,
     models::{
-        ERC20Token, EmptyData, NFTTrait, NamiLandERC1155NFTMetadata, NamiLandNFTOwnership,
-        NamiXStakedInfo, OwnershipItem,
+        AddressFailure, AssetDelta, CachePurgeResult, CollectionToken, ContractURIMetadata,
+        ERC20BalanceBatch, ERC20BalanceItem, ERC20Token, ERC721Ownership, ERC721TokenOwner,
+        EmptyData, FacetValue, HealthStatus, LPPosition, MetadataRefreshStatus, NFTAmountTotal,
+        NFTHolder, NFTMetadataFailure, NFTMetadataItem, NFTSupply, NFTTrait,
+        NamiLandERC1155NFTMetadata, NamiLandLockedNFTs, NamiLandNFTCatalogue,
+        NamiLandNFTCollection, NamiLandNFTFacets, NamiLandNFTHolders, NamiLandNFTMetadataBatch,
+        NamiLandNFTOwnership, NamiLandNFTOwnershipBatch, NamiLandNFTPermission, NamiXStakedInfo,
+        NamiXStakedInfoBatch, NetworkInventory, OwnershipItem, ResponseMeta, TokenPrice,
+        TokenPrices, TraitFacet, TransactionEvent, TransactionEventParam, TransactionStatus,
+        TransactionStatusType, WalletAssetChange, WalletInventory, WalletStreamBlock,
+        WebhookDeliveries, WebhookDelivery, WebhookDeliveryStatus, WebhookEventType,
+        WebhookPayload, WebhookSubscription, WebhookSubscriptions,
     },
 };
 
 pub fn new_router() -> Router {
     let router = Router::new()
         .route("/ping", get(ping))
+        .route("/v1/health", get(v1::health::get_health))
+        .route("/v1/prices", get(v1::price::get_prices))
+        .route("/v1/lp/position", get(v1::liquidity::get_lp_position))
+        .route(
+            "/v1/tx/status",
+            get(v1::transaction::get_transaction_status),
+        )
+        .route("/v1/wallet/stream", get(v1::stream::get_wallet_stream))
+        .route(
+            "/v1/wallet/:public_address/inventory",
+            get(v1::inventory::get_inventory),
+        )
         .route(
             "/v1/neco-staked-info/:network/:public_address",
             get(v1::neco_stake::get_neco_staked_info),
         )
+        .route(
+            "/v1/neco-staked-info/batch",
+            post(v1::neco_stake::get_neco_staked_info_batch),
+        )
         .route(
             "/v1/namiland-game-item-nft/ownership",
             get(v1::namiland_erc1155::get_nft_ownership),
         )
+        .route(
+            "/v1/namiland-game-item-nft/ownership/batch",
+            post(v1::namiland_erc1155::get_nft_ownership_batch),
+        )
         .route(
             "/v1/namiland-game-item-nft/metadata/:chain_id/:nft_id",
             get(v1::namiland_erc1155::get_nft_metadata),
         )
+        .route(
+            "/v1/namiland-game-item-nft/catalogue",
+            get(v1::namiland_erc1155::get_nft_catalogue),
+        )
+        .route(
+            "/v1/namiland-game-item-nft/catalogue/facets",
+            get(v1::namiland_erc1155::get_nft_facets),
+        )
+        .route(
+            "/v1/namiland-game-item-nft/holders",
+            get(v1::namiland_erc1155::get_nft_holders),
+        )
+        .route(
+            "/v1/namiland-game-item-nft/locked",
+            get(v1::namiland_erc1155::get_locked_nfts),
+        )
+        .route(
+            "/v1/namiland-game-item-nft/permission",
+            get(v1::namiland_erc1155::get_nft_permission),
+        )
+        .route(
+            "/v1/namiland-game-item-nft/collection",
+            get(v1::namiland_erc1155::get_nft_collection),
+        )
+        .route(
+            "/v1/namiland-game-item-nft/contract-uri/:chain_id",
+            get(v1::namiland_erc1155::get_contract_uri),
+        )
+        .route(
+            "/v1/namiland-game-item-nft/image/:chain_id/:nft_id",
+            get(v1::namiland_erc1155::get_nft_image),
+        )
+        .route(
+            "/v1/namiland-game-item-nft/metadata/batch",
+            post(v1::namiland_erc1155::get_nft_metadata_batch),
+        )
         .route("/v1/erc20/balance", get(v1::erc20::get_erc20_balance))
+        .route(
+            "/v1/erc20/balance/batch",
+            post(v1::erc20::get_erc20_balance_batch),
+        )
+        .route(
+            "/v1/erc721/ownership",
+            get(v1::erc721::get_erc721_ownership),
+        )
+        .route(
+            "/v1/erc721/metadata/:chain_id/:contract_address/:token_id",
+            get(v1::erc721::get_erc721_metadata),
+        )
+        .route(
+            "/v1/erc721/owner/:chain_id/:contract_address/:token_id",
+            get(v1::erc721::get_erc721_owner),
+        )
+        .route(
+            "/v1/admin/metadata-cache",
+            delete(v1::admin::purge_metadata_cache),
+        )
+        .route(
+            "/v1/admin/webhooks",
+            get(v1::webhook::get_webhooks).post(v1::webhook::register_webhook),
+        )
+        .route(
+            "/v1/admin/webhooks/:id",
+            delete(v1::webhook::delete_webhook),
+        )
+        .route(
+            "/v1/admin/webhooks/:id/deliveries",
+            get(v1::webhook::get_webhook_deliveries),
+        )
+        // network, block, cache and latency of responses, see `Response.meta`.
+        .layer(middleware::from_fn(track_request_meta))
+        // cached results, `ETag` and `Cache-Control` by the `[http_cache]` config.
+        .layer(middleware::from_fn(http_cache))
         .layer(
             CorsLayer::new()
                 .allow_origin(Any)
@@ -80,26 +200,125 @@ async fn ping() -> &'static str {
     modifiers(&RemoteApiServer),
     paths(
         ping,
+        v1::health::get_health,
+        v1::price::get_prices,
+        v1::liquidity::get_lp_position,
+        v1::transaction::get_transaction_status,
+        v1::stream::get_wallet_stream,
+        v1::inventory::get_inventory,
         v1::neco_stake::get_neco_staked_info,
+        v1::neco_stake::get_neco_staked_info_batch,
         v1::erc20::get_erc20_balance,
+        v1::erc20::get_erc20_balance_batch,
+        v1::erc721::get_erc721_ownership,
+        v1::erc721::get_erc721_metadata,
+        v1::erc721::get_erc721_owner,
         v1::namiland_erc1155::get_nft_ownership,
-        v1::namiland_erc1155::get_nft_metadata
+        v1::namiland_erc1155::get_nft_ownership_batch,
+        v1::namiland_erc1155::get_nft_metadata,
+        v1::namiland_erc1155::get_nft_catalogue,
+        v1::namiland_erc1155::get_nft_facets,
+        v1::namiland_erc1155::get_nft_holders,
+        v1::namiland_erc1155::get_locked_nfts,
+        v1::namiland_erc1155::get_nft_permission,
+        v1::namiland_erc1155::get_nft_collection,
+        v1::namiland_erc1155::get_contract_uri,
+        v1::namiland_erc1155::get_nft_image,
+        v1::namiland_erc1155::get_nft_metadata_batch,
+        v1::admin::purge_metadata_cache,
+        v1::webhook::register_webhook,
+        v1::webhook::get_webhooks,
+        v1::webhook::delete_webhook,
+        v1::webhook::get_webhook_deliveries
     ),
     components(
         schemas(
             NetworkType,
             EmptyData,
+            ResponseMeta,
             ERC20Token,
             NFTTrait,
             NamiLandERC1155NFTMetadata,
             OwnershipItem,
             NamiLandNFTOwnership,
+            NFTAmountTotal,
+            AddressFailure,
+            NamiLandNFTOwnershipBatch,
+            ERC20BalanceItem,
+            ERC20BalanceBatch,
+            NamiXStakedInfoBatch,
+            ERC721Ownership,
+            ERC721TokenOwner,
+            NFTHolder,
+            NFTSupply,
+            NamiLandNFTHolders,
+            NamiLandNFTCatalogue,
+            FacetValue,
+            TraitFacet,
+            NamiLandNFTFacets,
+            NamiLandLockedNFTs,
+            NamiLandNFTPermission,
+            CollectionToken,
+            NamiLandNFTCollection,
+            ContractURIMetadata,
+            CachePurgeResult,
+            MetadataRefreshStatus,
+            HealthStatus,
+            NetworkInventory,
+            WalletInventory,
+            TokenPrice,
+            TokenPrices,
+            LPPosition,
+            TransactionStatus,
+            TransactionStatusType,
+            TransactionEvent,
+            TransactionEventParam,
+            WalletAssetChange,
+            WalletStreamBlock,
+            AssetDelta,
+            WebhookEventType,
+            WebhookSubscription,
+            WebhookSubscriptions,
+            WebhookPayload,
+            WebhookDeliveryStatus,
+            WebhookDelivery,
+            WebhookDeliveries,
+            RegisterWebhookRequest,
+            NFTMetadataItem,
+            NFTMetadataFailure,
+            NamiLandNFTMetadataBatch,
+            GetNFTMetadataBatchRequest,
+            GetNFTOwnershipBatchRequest, RegisterWebhookRequest,
+            GetERC20BalanceBatchRequest,
+            GetNECOStakedInfoBatchRequest,
             ErrorResponse,
             NamiXStakedInfo,
             NECOStakedInfoResponse,
             ERC20TokenResponse,
             ERC1155OwnershipResponse,
-            ERC1155MetadataResponse
+            ERC1155OwnershipBatchResponse,
+            ERC20BalanceBatchResponse,
+            NECOStakedInfoBatchResponse,
+            ERC1155MetadataResponse,
+            ERC721OwnershipResponse,
+            ERC721OwnerResponse,
+            ERC721MetadataResponse,
+            ERC1155HoldersResponse,
+            ERC1155LockedResponse,
+            ERC1155PermissionResponse,
+            ERC1155CollectionResponse,
+            ERC1155CatalogueResponse,
+            ERC1155FacetsResponse,
+            CachePurgeResponse,
+            ERC1155MetadataBatchResponse,
+            HealthResponse,
+            WalletInventoryResponse,
+            TokenPricesResponse,
+            LPPositionResponse,
+            TransactionStatusResponse,
+            WebhookSubscriptionResponse,
+            WebhookSubscriptionsResponse,
+            WebhookDeliveriesResponse
         ),
     ),
     tags(
@@ -121,26 +340,125 @@ impl Modify for RemoteApiServer {
     modifiers(&LocalApiServer),
     paths(
         ping,
+        v1::health::get_health,
+        v1::price::get_prices,
+        v1::liquidity::get_lp_position,
+        v1::transaction::get_transaction_status,
+        v1::stream::get_wallet_stream,
+        v1::inventory::get_inventory,
         v1::neco_stake::get_neco_staked_info,
+        v1::neco_stake::get_neco_staked_info_batch,
         v1::erc20::get_erc20_balance,
+        v1::erc20::get_erc20_balance_batch,
+        v1::erc721::get_erc721_ownership,
+        v1::erc721::get_erc721_metadata,
+        v1::erc721::get_erc721_owner,
         v1::namiland_erc1155::get_nft_ownership,
-        v1::namiland_erc1155::get_nft_metadata
+        v1::namiland_erc1155::get_nft_ownership_batch,
+        v1::namiland_erc1155::get_nft_metadata,
+        v1::namiland_erc1155::get_nft_catalogue,
+        v1::namiland_erc1155::get_nft_facets,
+        v1::namiland_erc1155::get_nft_holders,
+        v1::namiland_erc1155::get_locked_nfts,
+        v1::namiland_erc1155::get_nft_permission,
+        v1::namiland_erc1155::get_nft_collection,
+        v1::namiland_erc1155::get_contract_uri,
+        v1::namiland_erc1155::get_nft_image,
+        v1::namiland_erc1155::get_nft_metadata_batch,
+        v1::admin::purge_metadata_cache,
+        v1::webhook::register_webhook,
+        v1::webhook::get_webhooks,
+        v1::webhook::delete_webhook,
+        v1::webhook::get_webhook_deliveries
     ),
     components(
         schemas(
             NetworkType,
             EmptyData,
+            ResponseMeta,
             ERC20Token,
             NFTTrait,
             NamiLandERC1155NFTMetadata,
             OwnershipItem,
             NamiLandNFTOwnership,
+            NFTAmountTotal,
+            AddressFailure,
+            NamiLandNFTOwnershipBatch,
+            ERC20BalanceItem,
+            ERC20BalanceBatch,
+            NamiXStakedInfoBatch,
+            ERC721Ownership,
+            ERC721TokenOwner,
+            NFTHolder,
+            NFTSupply,
+            NamiLandNFTHolders,
+            NamiLandNFTCatalogue,
+            FacetValue,
+            TraitFacet,
+            NamiLandNFTFacets,
+            NamiLandLockedNFTs,
+            NamiLandNFTPermission,
+            CollectionToken,
+            NamiLandNFTCollection,
+            ContractURIMetadata,
+            CachePurgeResult,
+            MetadataRefreshStatus,
+            HealthStatus,
+            NetworkInventory,
+            WalletInventory,
+            TokenPrice,
+            TokenPrices,
+            LPPosition,
+            TransactionStatus,
+            TransactionStatusType,
+            TransactionEvent,
+            TransactionEventParam,
+            WalletAssetChange,
+            WalletStreamBlock,
+            AssetDelta,
+            WebhookEventType,
+            WebhookSubscription,
+            WebhookSubscriptions,
+            WebhookPayload,
+            WebhookDeliveryStatus,
+            WebhookDelivery,
+            WebhookDeliveries,
+            RegisterWebhookRequest,
+            NFTMetadataItem,
+            NFTMetadataFailure,
+            NamiLandNFTMetadataBatch,
+            GetNFTMetadataBatchRequest,
+            GetNFTOwnershipBatchRequest, RegisterWebhookRequest,
+            GetERC20BalanceBatchRequest,
+            GetNECOStakedInfoBatchRequest,
             ErrorResponse,
             NamiXStakedInfo,
             NECOStakedInfoResponse,
             ERC20TokenResponse,
             ERC1155OwnershipResponse,
-            ERC1155MetadataResponse
+            ERC1155OwnershipBatchResponse,
+            ERC20BalanceBatchResponse,
+            NECOStakedInfoBatchResponse,
+            ERC1155MetadataResponse,
+            ERC721OwnershipResponse,
+            ERC721OwnerResponse,
+            ERC721MetadataResponse,
+            ERC1155HoldersResponse,
+            ERC1155LockedResponse,
+            ERC1155PermissionResponse,
+            ERC1155CollectionResponse,
+            ERC1155CatalogueResponse,
+            ERC1155FacetsResponse,
+            CachePurgeResponse,
+            ERC1155MetadataBatchResponse,
+            HealthResponse,
+            WalletInventoryResponse,
+            TokenPricesResponse,
+            LPPositionResponse,
+            TransactionStatusResponse,
+            WebhookSubscriptionResponse,
+            WebhookSubscriptionsResponse,
+            WebhookDeliveriesResponse
         ),
     ),
     tags(
@@ -1,3 +1,14 @@
+pub mod asset_events;
+pub mod catalogue;
 pub mod erc20;
+pub mod erc721;
+pub mod image_proxy;
+pub mod inventory;
+pub mod liquidity;
+pub mod multi_address;
 pub mod namiland_erc1155;
 pub mod neco_stake;
+pub mod nft_metadata;
+pub mod price;
+pub mod transaction;
+pub mod webhook;
@@ -0,0 +1,524 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use ethers::{
+    prelude::Lazy,
+    providers::Middleware,
+    types::{Address, H256, U256},
+};
+use tokio::sync::{broadcast, mpsc, Mutex};
+
+use crate::{
+    common::{
+        address::{checksum_address, get_contract_address},
+        config::config,
+        defines::{
+            Error, NetworkType, SupportedContractType, NFT_TRANSFER_LOG_BLOCK_RANGE,
+            WALLET_STREAM_CHANNEL_SIZE, WALLET_STREAM_MAX_RESUME_BLOCKS, WALLET_STREAM_POLL_SECS,
+        },
+        provider::ProviderManager,
+    },
+    models::{AssetDelta, WalletAssetChange, WalletStreamBlock},
+    services::{
+        erc20::{ERC20Contract, ERC20Service},
+        namiland_erc1155::NamiLandERC1155Contract,
+        neco_stake::NecoStakeService,
+    },
+};
+
+// an erc20 or erc1155 transfer log of a watched contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetTransfer {
+    pub block_number: u64,
+    pub tx_hash: H256,
+    pub log_index: u64,
+    pub contract_address: Address,
+    pub from: Address,
+    pub to: Address,
+    // erc20 transfers have a single amount without nft id.
+    pub nft_ids: Vec<U256>,
+    pub amounts: Vec<U256>,
+}
+
+// transfers of the watched contracts in a range of blocks.
+#[derive(Debug, Clone)]
+pub struct AssetBlocks {
+    pub from_block: u64,
+    pub to_block: u64,
+    pub transfers: Vec<AssetTransfer>,
+}
+
+// an item pushed to a wallet stream.
+#[derive(Debug, Clone)]
+pub enum WalletStreamItem {
+    Change(Box<WalletAssetChange>),
+    Block(WalletStreamBlock),
+    Error(String),
+}
+
+// One watcher per network polls new blocks and broadcasts the transfers to every
+// subscribed stream, it stops when the last subscriber is gone.
+static WATCHERS: Lazy<Mutex<HashMap<NetworkType, broadcast::Sender<Arc<AssetBlocks>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub async fn subscribe(network: NetworkType) -> broadcast::Receiver<Arc<AssetBlocks>> {
+    let mut watchers = WATCHERS.lock().await;
+    if let Some(sender) = watchers.get(&network) {
+        return sender.subscribe();
+    }
+    let (sender, receiver) = broadcast::channel(WALLET_STREAM_CHANNEL_SIZE);
+    watchers.insert(network, sender.clone());
+    tokio::spawn(watch_blocks(network, sender));
+    receiver
+}
+
+async fn watch_blocks(network: NetworkType, sender: broadcast::Sender<Arc<AssetBlocks>>) {
+    let mut next_block = None;
+    loop {
+        tokio::time::sleep(Duration::from_secs(WALLET_STREAM_POLL_SECS)).await;
+        {
+            let mut watchers = WATCHERS.lock().await;
+            if sender.receiver_count() == 0 {
+                watchers.remove(&network);
+                return;
+            }
+        }
+
+        let latest_block = match get_latest_block(network).await {
+            Ok(latest_block) => latest_block,
+            Err(err) => {
+                log::warn!("watch blocks of {} failed: {}", network, err);
+                continue;
+            }
+        };
+        // only blocks after the watcher started are broadcast.
+        let from_block = *next_block.get_or_insert(latest_block + 1);
+        if from_block > latest_block {
+            continue;
+        }
+        let to_block = (from_block + NFT_TRANSFER_LOG_BLOCK_RANGE - 1).min(latest_block);
+        match get_asset_transfers(network, from_block, to_block).await {
+            Ok(transfers) => {
+                let _ = sender.send(Arc::new(AssetBlocks {
+                    from_block,
+                    to_block,
+                    transfers,
+                }));
+                next_block = Some(to_block + 1);
+            }
+            Err(err) => log::warn!("watch blocks of {} failed: {}", network, err),
+        }
+    }
+}
+
+async fn get_latest_block(network: NetworkType) -> Result<u64, Error> {
+    let client = match ProviderManager::instance().get_provider(network) {
+        Some(client) => client,
+        None => return Err("get provider failed".into()),
+    };
+    Ok(client.get_block_number().await?.as_u64())
+}
+
+// erc20 tokens of the inventory, NECO and NFISH on the network.
+fn watched_erc20_tokens(network: NetworkType) -> Vec<Address> {
+    let mut tokens: Vec<Address> = config()
+        .erc20_tokens
+        .iter()
+        .filter(|token| token.network == network)
+        .map(|token| token.address)
+        .collect();
+    for contract_type in [SupportedContractType::NAMIX, SupportedContractType::FISHX] {
+        if let Ok(address) = get_contract_address(contract_type, network) {
+            if !tokens.contains(&address) {
+                tokens.push(address);
+            }
+        }
+    }
+    tokens
+}
+
+// get transfers of the watched erc20 tokens and erc1155 collections, ordered as in the chain.
+pub async fn get_asset_transfers(
+    network: NetworkType,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<AssetTransfer>, Error> {
+    let mut transfers = vec![];
+    for token in watched_erc20_tokens(network) {
+        transfers.extend(get_erc20_transfers(network, token, from_block, to_block).await?);
+    }
+    for collection in config()
+        .erc1155_collections
+        .iter()
+        .filter(|collection| collection.network == network)
+    {
+        transfers.extend(
+            get_erc1155_transfers(network, collection.address, from_block, to_block).await?,
+        );
+    }
+    transfers.sort_by_key(|transfer| (transfer.block_number, transfer.log_index));
+    Ok(transfers)
+}
+
+pub async fn get_erc20_transfers(
+    network: NetworkType,
+    token: Address,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<AssetTransfer>, Error> {
+    let client = match ProviderManager::instance().get_provider(network) {
+        Some(client) => client,
+        None => return Err("get provider failed".into()),
+    };
+    let events = ERC20Contract::new(token, client)
+        .transfer_filter()
+        .from_block(from_block)
+        .to_block(to_block)
+        .query_with_meta()
+        .await?;
+    Ok(events
+        .into_iter()
+        .map(|(event, meta)| AssetTransfer {
+            block_number: meta.block_number.as_u64(),
+            tx_hash: meta.transaction_hash,
+            log_index: meta.log_index.as_u64(),
+            contract_address: meta.address,
+            from: event.from,
+            to: event.to,
+            nft_ids: vec![],
+            amounts: vec![event.value],
+        })
+        .collect())
+}
+
+// get `TransferSingle` and `TransferBatch` events of an erc1155 contract, ordered as in the chain.
+pub async fn get_erc1155_transfers(
+    network: NetworkType,
+    contract: Address,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<AssetTransfer>, Error> {
+    let client = match ProviderManager::instance().get_provider(network) {
+        Some(client) => client,
+        None => return Err("get provider failed".into()),
+    };
+    let contract = NamiLandERC1155Contract::new(contract, client);
+    let singles = contract
+        .transfer_single_filter()
+        .from_block(from_block)
+        .to_block(to_block)
+        .query_with_meta()
+        .await?;
+    let batches = contract
+        .transfer_batch_filter()
+        .from_block(from_block)
+        .to_block(to_block)
+        .query_with_meta()
+        .await?;
+    let mut transfers: Vec<AssetTransfer> = singles
+        .into_iter()
+        .map(|(event, meta)| AssetTransfer {
+            block_number: meta.block_number.as_u64(),
+            tx_hash: meta.transaction_hash,
+            log_index: meta.log_index.as_u64(),
+            contract_address: meta.address,
+            from: event.from,
+            to: event.to,
+            nft_ids: vec![event.id],
+            amounts: vec![event.value],
+        })
+        .collect();
+    transfers.extend(batches.into_iter().map(|(event, meta)| AssetTransfer {
+        block_number: meta.block_number.as_u64(),
+        tx_hash: meta.transaction_hash,
+        log_index: meta.log_index.as_u64(),
+        contract_address: meta.address,
+        from: event.from,
+        to: event.to,
+        nft_ids: event.ids,
+        amounts: event.values,
+    }));
+    transfers.sort_by_key(|transfer| (transfer.block_number, transfer.log_index));
+    Ok(transfers)
+}
+
+// Stream asset changes of the addresses to `sender` until the receiver is dropped.
+// With `from_block` the transfers since that block are replayed first, then new blocks
+// are followed. A block marker is sent after the changes of every scanned range.
+pub async fn stream_wallet_changes(
+    network: NetworkType,
+    public_addresses: Vec<Address>,
+    from_block: Option<u64>,
+    sender: mpsc::Sender<WalletStreamItem>,
+) {
+    let mut receiver = subscribe(network).await;
+    let mut next_block = None;
+    if let Some(from_block) = from_block {
+        let latest_block = match get_latest_block(network).await {
+            Ok(latest_block) => latest_block,
+            Err(err) => {
+                let _ = sender.send(WalletStreamItem::Error(err.to_string())).await;
+                return;
+            }
+        };
+        if latest_block.saturating_sub(from_block) > WALLET_STREAM_MAX_RESUME_BLOCKS {
+            let message = format!(
+                "from block is more than {} blocks behind the latest block",
+                WALLET_STREAM_MAX_RESUME_BLOCKS
+            );
+            let _ = sender.send(WalletStreamItem::Error(message)).await;
+            return;
+        }
+        next_block = Some(from_block);
+        if from_block <= latest_block {
+            let items = replay_blocks(network, &public_addresses, from_block, latest_block).await;
+            if !send_items(items, &sender).await {
+                return;
+            }
+            next_block = Some(latest_block + 1);
+        }
+    }
+
+    loop {
+        let blocks = match receiver.recv().await {
+            Ok(blocks) => blocks,
+            // missed ranges are fetched again below.
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        let from_block = next_block.unwrap_or(blocks.from_block);
+        if blocks.to_block < from_block {
+            continue;
+        }
+        let mut transfers = vec![];
+        if from_block < blocks.from_block {
+            match get_asset_transfers(network, from_block, blocks.from_block - 1).await {
+                Ok(missed) => transfers.extend(missed),
+                Err(err) => {
+                    let _ = sender.send(WalletStreamItem::Error(err.to_string())).await;
+                    return;
+                }
+            }
+        }
+        transfers.extend(
+            blocks
+                .transfers
+                .iter()
+                .filter(|transfer| transfer.block_number >= from_block)
+                .cloned(),
+        );
+        let items = wallet_items(network, &public_addresses, &transfers, blocks.to_block).await;
+        if !send_items(Ok(items), &sender).await {
+            return;
+        }
+        next_block = Some(blocks.to_block + 1);
+    }
+}
+
+async fn replay_blocks(
+    network: NetworkType,
+    public_addresses: &[Address],
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<WalletStreamItem>, Error> {
+    let mut items = vec![];
+    let mut start = from_block;
+    while start <= to_block {
+        let end = (start + NFT_TRANSFER_LOG_BLOCK_RANGE - 1).min(to_block);
+        let transfers = get_asset_transfers(network, start, end).await?;
+        items.extend(wallet_items(network, public_addresses, &transfers, end).await);
+        start = end + 1;
+    }
+    Ok(items)
+}
+
+// send the items, false when the stream is closed or failed.
+async fn send_items(
+    items: Result<Vec<WalletStreamItem>, Error>,
+    sender: &mpsc::Sender<WalletStreamItem>,
+) -> bool {
+    let items = match items {
+        Ok(items) => items,
+        Err(err) => {
+            let _ = sender.send(WalletStreamItem::Error(err.to_string())).await;
+            return false;
+        }
+    };
+    for item in items {
+        if sender.send(item).await.is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+// changes of the addresses in the transfers, with current erc20 balances and staked info,
+// followed by the block marker of `to_block`.
+async fn wallet_items(
+    network: NetworkType,
+    public_addresses: &[Address],
+    transfers: &[AssetTransfer],
+    to_block: u64,
+) -> Vec<WalletStreamItem> {
+    let stake_contract = config().stake_contract(network);
+    let mut items = vec![];
+    for (public_address, transfer, deltas) in wallet_deltas(transfers, public_addresses) {
+        let mut change = WalletAssetChange {
+            public_address: checksum_address(&public_address),
+            network,
+            block_number: transfer.block_number,
+            tx_hash: format!("{:?}", transfer.tx_hash),
+            log_index: transfer.log_index,
+            contract_address: checksum_address(&transfer.contract_address),
+            asset_type: change_asset_type(transfer, public_address, stake_contract).to_string(),
+            deltas,
+            ..Default::default()
+        };
+        if transfer.nft_ids.is_empty() {
+            change.balance = match ERC20Service::from_address(network, transfer.contract_address) {
+                Ok(service) => service.get_token(public_address).await.ok(),
+                Err(_) => None,
+            };
+        }
+        if change.asset_type == "stake" {
+            change.staked = match NecoStakeService::new(network) {
+                Ok(service) => service.get_staked_info(public_address).await.ok(),
+                Err(_) => None,
+            };
+        }
+        items.push(WalletStreamItem::Change(Box::new(change)));
+    }
+    items.push(WalletStreamItem::Block(WalletStreamBlock {
+        network,
+        block_number: to_block,
+    }));
+    items
+}
+
+// erc1155 for game item transfers, erc20 for token transfers, and stake for token
+// transfers between the address and the stake contract of the network.
+pub fn change_asset_type(
+    transfer: &AssetTransfer,
+    public_address: Address,
+    stake_contract: Option<Address>,
+) -> &'static str {
+    if !transfer.nft_ids.is_empty() {
+        return "erc1155";
+    }
+    let counterparty = match transfer.from == public_address {
+        true => transfer.to,
+        false => transfer.from,
+    };
+    match stake_contract == Some(counterparty) {
+        true => "stake",
+        false => "erc20",
+    }
+}
+
+// signed amount changes of each address in each transfer, transfers to self are skipped.
+pub fn wallet_deltas<'a>(
+    transfers: &'a [AssetTransfer],
+    public_addresses: &[Address],
+) -> Vec<(Address, &'a AssetTransfer, Vec<AssetDelta>)> {
+    let mut deltas = vec![];
+    for transfer in transfers {
+        if transfer.from == transfer.to {
+            continue;
+        }
+        for public_address in public_addresses {
+            let sign = if transfer.from == *public_address {
+                "-"
+            } else if transfer.to == *public_address {
+                ""
+            } else {
+                continue;
+            };
+            let amounts = transfer
+                .amounts
+                .iter()
+                .enumerate()
+                .map(|(index, amount)| AssetDelta {
+                    nft_id: transfer.nft_ids.get(index).map(|id| id.to_string()),
+                    amount: format!("{}{}", sign, amount),
+                })
+                .collect();
+            deltas.push((*public_address, transfer, amounts));
+        }
+    }
+    deltas
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::{Address, H256, U256};
+
+    use crate::services::asset_events::{change_asset_type, wallet_deltas, AssetTransfer};
+
+    #[test]
+    fn test_wallet_deltas() {
+        let wallet = Address::from_low_u64_be(1);
+        let other = Address::from_low_u64_be(2);
+        let transfer = AssetTransfer {
+            block_number: 10,
+            tx_hash: H256::zero(),
+            log_index: 0,
+            contract_address: Address::from_low_u64_be(3),
+            from: wallet,
+            to: other,
+            nft_ids: vec![],
+            amounts: vec![U256::from(500)],
+        };
+        let batch = AssetTransfer {
+            log_index: 1,
+            from: other,
+            to: wallet,
+            nft_ids: vec![U256::from(10001), U256::from(10002)],
+            amounts: vec![U256::from(1), U256::from(2)],
+            ..transfer.clone()
+        };
+        let to_self = AssetTransfer {
+            log_index: 2,
+            to: wallet,
+            ..transfer.clone()
+        };
+        let transfers = [transfer, batch, to_self];
+
+        let deltas = wallet_deltas(&transfers, &[wallet]);
+        assert_eq!(deltas.len(), 2);
+        assert_eq!(deltas[0].2[0].amount, "-500");
+        assert_eq!(deltas[0].2[0].nft_id, None);
+        assert_eq!(deltas[1].2.len(), 2);
+        assert_eq!(deltas[1].2[1].nft_id.as_deref(), Some("10002"));
+        assert_eq!(deltas[1].2[1].amount, "2");
+        assert!(wallet_deltas(&transfers, &[Address::from_low_u64_be(4)]).is_empty());
+    }
+
+    #[test]
+    fn test_change_asset_type() {
+        let wallet = Address::from_low_u64_be(1);
+        let stake = Address::from_low_u64_be(9);
+        let transfer = AssetTransfer {
+            block_number: 10,
+            tx_hash: H256::zero(),
+            log_index: 0,
+            contract_address: Address::from_low_u64_be(3),
+            from: wallet,
+            to: stake,
+            nft_ids: vec![],
+            amounts: vec![U256::from(500)],
+        };
+        assert_eq!(change_asset_type(&transfer, wallet, Some(stake)), "stake");
+        let unstake = AssetTransfer {
+            from: stake,
+            to: wallet,
+            ..transfer.clone()
+        };
+        assert_eq!(change_asset_type(&unstake, wallet, Some(stake)), "stake");
+        // networks without a stake contract have plain erc20 transfers.
+        assert_eq!(change_asset_type(&transfer, wallet, None), "erc20");
+        let items = AssetTransfer {
+            nft_ids: vec![U256::from(10001)],
+            ..transfer
+        };
+        assert_eq!(change_asset_type(&items, wallet, Some(stake)), "erc1155");
+    }
+}
@@ -0,0 +1,323 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use ethers::types::U256;
+use serde_json::Value;
+
+use crate::{
+    common::{
+        address::checksum_address,
+        defines::{Error, NetworkType},
+        pagination::{paginate, SortOrder},
+    },
+    models::{
+        FacetValue, NFTMetadataItem, NamiLandERC1155NFTMetadata, NamiLandNFTCatalogue,
+        NamiLandNFTFacets, TraitFacet,
+    },
+    services::namiland_erc1155::NamiLandERC1155Service,
+};
+
+// Filters over nft metadata, all given filters must match. Text is compared case-insensitively.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CatalogueFilter {
+    // values of the same trait type match any of them, different trait types must all match.
+    pub traits: BTreeMap<String, Vec<String>>,
+    pub nft_type1: Option<String>,
+    pub nft_type2: Option<String>,
+    // substring of the name.
+    pub name: Option<String>,
+}
+
+impl CatalogueFilter {
+    // parse filters from query string, traits are given as `type:value,type:value`.
+    pub fn parse(
+        traits: Option<&str>,
+        nft_type1: Option<&str>,
+        nft_type2: Option<&str>,
+        name: Option<&str>,
+    ) -> Result<CatalogueFilter, Error> {
+        let mut filter = CatalogueFilter {
+            nft_type1: normalize(nft_type1),
+            nft_type2: normalize(nft_type2),
+            name: normalize(name),
+            ..Default::default()
+        };
+        for pair in traits.unwrap_or_default().split(',') {
+            if pair.trim().is_empty() {
+                continue;
+            }
+            let (trait_type, value) = pair
+                .split_once(':')
+                .ok_or_else(|| format!("trait filter is invalid: {}", pair))?;
+            let (trait_type, value) = (trait_type.trim(), value.trim());
+            if trait_type.is_empty() || value.is_empty() {
+                return Err(format!("trait filter is invalid: {}", pair).into());
+            }
+            filter
+                .traits
+                .entry(trait_type.to_lowercase())
+                .or_default()
+                .push(value.to_lowercase());
+        }
+        Ok(filter)
+    }
+
+    pub fn matches(&self, metadata: &NamiLandERC1155NFTMetadata) -> bool {
+        if matches!(&self.nft_type1, Some(nft_type1) if *nft_type1 != metadata.nft_type1.to_lowercase())
+        {
+            return false;
+        }
+        if matches!(&self.nft_type2, Some(nft_type2) if *nft_type2 != metadata.nft_type2.to_lowercase())
+        {
+            return false;
+        }
+        if matches!(&self.name, Some(name) if !metadata.name.to_lowercase().contains(name.as_str()))
+        {
+            return false;
+        }
+        self.traits.iter().all(|(trait_type, values)| {
+            metadata.attributes.iter().any(|attribute| {
+                attribute.trait_type.to_lowercase() == *trait_type
+                    && values.contains(&trait_value(&attribute.value).to_lowercase())
+            })
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogueSortField {
+    NftId,
+    Name,
+}
+
+impl CatalogueSortField {
+    // parse sort field from query string, default is nft id.
+    pub fn parse(value: Option<&str>) -> Option<CatalogueSortField> {
+        match value {
+            None | Some("nft_id") => Some(CatalogueSortField::NftId),
+            Some("name") => Some(CatalogueSortField::Name),
+            Some(_) => None,
+        }
+    }
+}
+
+// trait values are strings, numbers or booleans, compare them by their text.
+pub fn trait_value(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+// count items per nft type and per trait value.
+pub fn count_facets(
+    metadata: &[&NamiLandERC1155NFTMetadata],
+) -> (Vec<FacetValue>, Vec<FacetValue>, Vec<TraitFacet>) {
+    let mut nft_type1: BTreeMap<String, u64> = BTreeMap::new();
+    let mut nft_type2: BTreeMap<String, u64> = BTreeMap::new();
+    let mut traits: BTreeMap<String, BTreeMap<String, u64>> = BTreeMap::new();
+    for metadata in metadata {
+        if !metadata.nft_type1.is_empty() {
+            *nft_type1.entry(metadata.nft_type1.clone()).or_default() += 1;
+        }
+        if !metadata.nft_type2.is_empty() {
+            *nft_type2.entry(metadata.nft_type2.clone()).or_default() += 1;
+        }
+        for attribute in &metadata.attributes {
+            *traits
+                .entry(attribute.trait_type.clone())
+                .or_default()
+                .entry(trait_value(&attribute.value))
+                .or_default() += 1;
+        }
+    }
+
+    (
+        facet_values(nft_type1),
+        facet_values(nft_type2),
+        traits
+            .into_iter()
+            .map(|(trait_type, values)| TraitFacet {
+                trait_type,
+                values: facet_values(values),
+            })
+            .collect(),
+    )
+}
+
+// Catalogue of all game items, built from the cached metadata of every known nft id.
+pub struct CatalogueService {
+    erc1155_service: NamiLandERC1155Service,
+}
+
+impl CatalogueService {
+    pub fn new(network: NetworkType) -> Result<CatalogueService, Error> {
+        Ok(CatalogueService {
+            erc1155_service: NamiLandERC1155Service::new(network)?,
+        })
+    }
+}
+
+impl CatalogueService {
+    pub async fn get_catalogue(
+        &self,
+        filter: &CatalogueFilter,
+        sort_by: CatalogueSortField,
+        order: SortOrder,
+        page: usize,
+        page_size: usize,
+    ) -> Result<NamiLandNFTCatalogue, Error> {
+        let network = self.erc1155_service.network;
+        let mut items: Vec<(U256, NamiLandERC1155NFTMetadata)> = self
+            .get_items()
+            .await
+            .into_iter()
+            .filter(|(_, metadata)| filter.matches(metadata))
+            .collect();
+        items.sort_by(|a, b| {
+            let ordering = match sort_by {
+                CatalogueSortField::NftId => a.0.cmp(&b.0),
+                CatalogueSortField::Name => a.1.name.cmp(&b.1.name).then(a.0.cmp(&b.0)),
+            };
+            order.apply(ordering)
+        });
+
+        Ok(NamiLandNFTCatalogue {
+            network,
+            contract_address: checksum_address(&self.erc1155_service.collection.address),
+            total: items.len() as u64,
+            items: paginate(items, page, page_size)
+                .into_iter()
+                .map(|(nft_id, metadata)| NFTMetadataItem {
+                    nft_id: nft_id.to_string(),
+                    metadata,
+                })
+                .collect(),
+            page: page as u64,
+            page_size: page_size as u64,
+        })
+    }
+
+    pub async fn get_facets(&self, filter: &CatalogueFilter) -> Result<NamiLandNFTFacets, Error> {
+        let items = self.get_items().await;
+        let matched: Vec<&NamiLandERC1155NFTMetadata> = items
+            .iter()
+            .map(|(_, metadata)| metadata)
+            .filter(|metadata| filter.matches(metadata))
+            .collect();
+        let (nft_type1, nft_type2, traits) = count_facets(&matched);
+
+        Ok(NamiLandNFTFacets {
+            network: self.erc1155_service.network,
+            total: matched.len() as u64,
+            nft_type1,
+            nft_type2,
+            traits,
+        })
+    }
+
+    // metadata of every known nft id, ids whose metadata can not be fetched are left out.
+    async fn get_items(&self) -> Vec<(U256, NamiLandERC1155NFTMetadata)> {
+        let nft_ids = self.erc1155_service.get_known_nft_ids().await;
+        self.erc1155_service
+            .get_metadata_batch(&nft_ids)
+            .await
+            .into_iter()
+            .filter_map(|(nft_id, result)| result.ok().map(|metadata| (nft_id, metadata)))
+            .collect()
+    }
+}
+
+fn normalize(value: Option<&str>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty())
+}
+
+// facet values ordered by count, then by value.
+fn facet_values(counts: BTreeMap<String, u64>) -> Vec<FacetValue> {
+    let mut values: Vec<FacetValue> = counts
+        .into_iter()
+        .map(|(value, count)| FacetValue { value, count })
+        .collect();
+    values.sort_by(|a, b| match b.count.cmp(&a.count) {
+        Ordering::Equal => a.value.cmp(&b.value),
+        ordering => ordering,
+    });
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        models::{NFTTrait, NamiLandERC1155NFTMetadata},
+        services::catalogue::{count_facets, CatalogueFilter},
+    };
+
+    fn metadata(name: &str, nft_type1: &str, rarity: &str) -> NamiLandERC1155NFTMetadata {
+        NamiLandERC1155NFTMetadata {
+            name: name.to_string(),
+            nft_type1: nft_type1.to_string(),
+            attributes: vec![
+                NFTTrait {
+                    trait_type: "Rarity".to_string(),
+                    value: json!(rarity),
+                    display_type: None,
+                },
+                NFTTrait {
+                    trait_type: "Level".to_string(),
+                    value: json!(1),
+                    display_type: None,
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_filter_metadata() {
+        let rod = metadata("Bamboo Rod", "Rod", "Common");
+        let bait = metadata("Golden Bait", "Bait", "Rare");
+
+        let filter = CatalogueFilter::parse(None, Some("rod"), None, None).unwrap();
+        assert!(filter.matches(&rod) && !filter.matches(&bait));
+
+        let filter =
+            CatalogueFilter::parse(Some("rarity:rare,rarity:common,level:1"), None, None, None)
+                .unwrap();
+        assert!(filter.matches(&rod) && filter.matches(&bait));
+
+        let filter =
+            CatalogueFilter::parse(Some("rarity:rare"), None, None, Some("bamboo")).unwrap();
+        assert!(!filter.matches(&rod) && !filter.matches(&bait));
+
+        assert!(CatalogueFilter::parse(Some("rarity"), None, None, None).is_err());
+    }
+
+    #[test]
+    fn test_count_facets() {
+        let items = vec![
+            metadata("Bamboo Rod", "Rod", "Common"),
+            metadata("Carbon Rod", "Rod", "Rare"),
+            metadata("Golden Bait", "Bait", "Rare"),
+        ];
+        let (nft_type1, nft_type2, traits) = count_facets(&items.iter().collect::<Vec<_>>());
+
+        assert_eq!(nft_type1[0].value, "Rod");
+        assert_eq!(nft_type1[0].count, 2);
+        assert!(nft_type2.is_empty());
+        let rarity = traits
+            .iter()
+            .find(|facet| facet.trait_type == "Rarity")
+            .unwrap();
+        assert_eq!(
+            rarity
+                .values
+                .iter()
+                .map(|value| (value.value.as_str(), value.count))
+                .collect::<Vec<_>>(),
+            vec![("Rare", 2), ("Common", 1)]
+        );
+    }
+}
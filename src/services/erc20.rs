@@ -1,7 +1,11 @@
-use crate::common::{
-    address::get_contract_address,
-    defines::{Error, NetworkType, SupportedContractType},
-    provider::ProviderManager,
+use crate::{
+    common::{
+        address::get_contract_address,
+        defines::{Error, NetworkType, SupportedContractType},
+        finality::at_block,
+        provider::ProviderManager,
+    },
+    models::ERC20Token,
 };
 use ethers::{
     prelude::abigen,
@@ -15,34 +19,89 @@ abigen!(
     event_derives(serde::Deserialize, serde::Serialize)
 );
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ERC20Service {
     contract: ERC20Contract<Provider<Http>>,
+    block_number: Option<u64>,
 }
 
 impl ERC20Service {
-    pub fn new(contract_type: SupportedContractType, network: NetworkType) -> ERC20Service {
-        let client = ProviderManager::instance()
-            .get_provider(network)
-            .expect("get provider failed");
-        let address =
-            get_contract_address(contract_type, network).expect("get contract address failed");
+    pub fn new(
+        contract_type: SupportedContractType,
+        network: NetworkType,
+    ) -> Result<ERC20Service, Error> {
+        let address = get_contract_address(contract_type, network)?;
+        ERC20Service::from_address(network, address)
+    }
+
+    pub fn from_address(network: NetworkType, address: Address) -> Result<ERC20Service, Error> {
+        let client = match ProviderManager::instance().get_provider(network) {
+            Some(client) => client,
+            None => return Err("get provider failed".into()),
+        };
         let contract = ERC20Contract::new(address, client.clone());
-        ERC20Service { contract }
+        Ok(ERC20Service {
+            contract,
+            block_number: None,
+        })
+    }
+
+    // read at the block resolved from the `finality` param.
+    pub fn at_block(mut self, block_number: Option<u64>) -> ERC20Service {
+        self.block_number = block_number;
+        self
+    }
+
+    pub fn block_number(&self) -> Option<u64> {
+        self.block_number
+    }
+
+    pub fn address(&self) -> Address {
+        self.contract.address()
     }
 }
 
 impl ERC20Service {
     pub async fn get_symbol(&self) -> Result<String, Error> {
-        Ok(self.contract.symbol().call().await?)
+        Ok(at_block(self.contract.symbol(), self.block_number)
+            .call()
+            .await?)
     }
 
     pub async fn get_decimal(&self) -> Result<u8, Error> {
-        Ok(self.contract.decimals().call().await?)
+        Ok(at_block(self.contract.decimals(), self.block_number)
+            .call()
+            .await?)
+    }
+
+    pub async fn get_total_supply(&self) -> Result<U256, Error> {
+        Ok(at_block(self.contract.total_supply(), self.block_number)
+            .call()
+            .await?)
     }
 
     pub async fn get_balance(&self, account: &str) -> Result<U256, Error> {
         let address = account.parse::<Address>()?;
-        Ok(self.contract.balance_of(address).call().await?)
+        let balance_call = at_block(self.contract.balance_of(address), self.block_number);
+        Ok(balance_call.call().await?)
+    }
+
+    // get symbol, decimals and balance of an account together.
+    pub async fn get_token(&self, account: Address) -> Result<ERC20Token, Error> {
+        let symbol_call = at_block(self.contract.symbol(), self.block_number);
+        let decimals_call = at_block(self.contract.decimals(), self.block_number);
+        let balance_call = at_block(self.contract.balance_of(account), self.block_number);
+        let (symbol, decimal, amount) = tokio::try_join!(
+            symbol_call.call(),
+            decimals_call.call(),
+            balance_call.call()
+        )?;
+        Ok(ERC20Token {
+            symbol,
+            decimal,
+            amount: amount.to_string(),
+            block_number: self.block_number,
+            ..Default::default()
+        })
     }
 }
@@ -0,0 +1,290 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
+
+use ethers::{
+    prelude::{abigen, Lazy, Multicall},
+    providers::{Http, Middleware, Provider},
+    types::{Address, H256, U256},
+};
+use tokio::sync::{Mutex, Semaphore};
+
+use super::nft_metadata::{get_cached_metadata, MetadataKey};
+use crate::{
+    common::{
+        address::checksum_address,
+        defines::{
+            Error, NetworkType, ERC721_ENUMERABLE_INTERFACE_ID, ERC721_OWNERSHIP_MAX_TOKENS,
+            ERC721_TRANSFER_CANDIDATES_MAX, MULTICALL_BATCH_SIZE, NFT_METADATA_FETCH_CONCURRENCY,
+            NFT_TRANSFER_LOG_BLOCK_RANGE,
+        },
+        finality::{at_block, multicall_at_block},
+        multicall::{multicall_uints, multicall_value},
+        provider::ProviderManager,
+    },
+    models::{ERC721Ownership, ERC721TokenOwner, NFTMetadataItem, NamiLandERC1155NFTMetadata},
+};
+
+abigen!(
+    ERC721Contract,
+    "./src/abi/erc721.json",
+    event_derives(serde::Deserialize, serde::Serialize)
+);
+
+// whether a contract supports ERC721Enumerable, detected once per contract.
+static ERC721_ENUMERABLE_CACHES: Lazy<Mutex<HashMap<(NetworkType, Address), bool>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone)]
+pub struct ERC721Service {
+    pub contract: ERC721Contract<Provider<Http>>,
+    pub network: NetworkType,
+    block_number: Option<u64>,
+}
+
+impl ERC721Service {
+    pub fn new(network: NetworkType, contract_address: Address) -> Result<ERC721Service, Error> {
+        let client = match ProviderManager::instance().get_provider(network) {
+            Some(client) => client,
+            None => return Err("get provider failed".into()),
+        };
+        let contract = ERC721Contract::new(contract_address, client.clone());
+        Ok(ERC721Service {
+            contract,
+            network,
+            block_number: None,
+        })
+    }
+
+    // read at the resolved block, reads without a block are made at the latest block.
+    pub fn at_block(mut self, block_number: Option<u64>) -> ERC721Service {
+        self.block_number = block_number;
+        self
+    }
+}
+
+impl ERC721Service {
+    // get tokens owned by an address with their metadata. Tokens are enumerated by
+    // `tokenOfOwnerByIndex` when the contract is enumerable, otherwise by `Transfer`
+    // events received by the address since `from_block`, which is required then.
+    pub async fn get_ownership(
+        &self,
+        public_address: Address,
+        from_block: Option<u64>,
+    ) -> Result<ERC721Ownership, Error> {
+        let name_call = at_block(self.contract.name(), self.block_number);
+        let symbol_call = at_block(self.contract.symbol(), self.block_number);
+        let balance_call = at_block(self.contract.balance_of(public_address), self.block_number);
+        let (name, symbol, balance) =
+            tokio::join!(name_call.call(), symbol_call.call(), balance_call.call());
+        let balance = balance?;
+        // the balance is reported by the contract, any contract can be queried.
+        if balance > U256::from(ERC721_OWNERSHIP_MAX_TOKENS) {
+            return Err(format!(
+                "balance of {} tokens is over the limit of {}",
+                balance, ERC721_OWNERSHIP_MAX_TOKENS
+            )
+            .into());
+        }
+        let balance = balance.as_u64();
+
+        let enumerable = self.supports_enumerable().await;
+        let mut token_ids = match (enumerable, from_block) {
+            (true, _) => {
+                self.get_enumerated_token_ids(public_address, balance)
+                    .await?
+            }
+            (false, Some(from_block)) => {
+                self.get_received_token_ids(public_address, from_block)
+                    .await?
+            }
+            (false, None) => {
+                return Err(
+                    "contract is not enumerable, its transfers are scanned from start_block \
+                     of a configured collection"
+                        .into(),
+                )
+            }
+        };
+        token_ids.truncate(ERC721_OWNERSHIP_MAX_TOKENS as usize);
+
+        Ok(ERC721Ownership {
+            public_address: checksum_address(&public_address),
+            primary_name: None,
+            is_contract: None,
+            network: self.network,
+            contract_address: checksum_address(&self.contract.address()),
+            name: name.unwrap_or_default(),
+            symbol: symbol.unwrap_or_default(),
+            enumerable,
+            balance,
+            tokens: self.get_tokens(&token_ids).await,
+        })
+    }
+
+    pub async fn get_owner(&self, token_id: &U256) -> Result<ERC721TokenOwner, Error> {
+        let owner = at_block(self.contract.owner_of(*token_id), self.block_number)
+            .call()
+            .await?;
+        Ok(ERC721TokenOwner {
+            network: self.network,
+            contract_address: checksum_address(&self.contract.address()),
+            token_id: token_id.to_string(),
+            owner: checksum_address(&owner),
+        })
+    }
+
+    // get token metadata through the metadata cache shared with ERC1155 collections.
+    pub async fn get_metadata(&self, token_id: &U256) -> Result<NamiLandERC1155NFTMetadata, Error> {
+        let key: MetadataKey = (self.network, self.contract.address(), *token_id);
+        get_cached_metadata(key, async {
+            Ok(
+                at_block(self.contract.token_uri(*token_id), self.block_number)
+                    .call()
+                    .await?,
+            )
+        })
+        .await
+    }
+
+    // detect ERC721Enumerable by ERC165, contracts without ERC165 are not enumerable.
+    pub async fn supports_enumerable(&self) -> bool {
+        let key = (self.network, self.contract.address());
+        if let Some(enumerable) = ERC721_ENUMERABLE_CACHES.lock().await.get(&key) {
+            return *enumerable;
+        }
+        let result = at_block(
+            self.contract
+                .supports_interface(ERC721_ENUMERABLE_INTERFACE_ID),
+            self.block_number,
+        )
+        .call()
+        .await;
+        match result {
+            Ok(enumerable) => {
+                ERC721_ENUMERABLE_CACHES
+                    .lock()
+                    .await
+                    .insert(key, enumerable);
+                enumerable
+            }
+            // not cached, it may be a temporary rpc failure.
+            Err(_) => false,
+        }
+    }
+
+    async fn get_enumerated_token_ids(
+        &self,
+        public_address: Address,
+        balance: u64,
+    ) -> Result<Vec<U256>, Error> {
+        let indexes: Vec<U256> = (0..balance).map(U256::from).collect();
+        let mut multicall = multicall_at_block(
+            Multicall::new(self.contract.client(), None).await?,
+            self.block_number,
+        );
+        let mut token_ids = multicall_uints(&mut multicall, &indexes, |index| {
+            self.contract.token_of_owner_by_index(public_address, index)
+        })
+        .await?;
+        token_ids.sort();
+        Ok(token_ids)
+    }
+
+    // tokens received by the address, which are still owned by it.
+    async fn get_received_token_ids(
+        &self,
+        public_address: Address,
+        from_block: u64,
+    ) -> Result<Vec<U256>, Error> {
+        let latest_block = match self.block_number {
+            Some(block_number) => block_number,
+            None => self.contract.client().get_block_number().await?.as_u64(),
+        };
+        let filter = || {
+            self.contract
+                .transfer_filter()
+                .topic2(H256::from(public_address))
+        };
+
+        // most providers accept a topic filtered query over the whole range,
+        // scan in chunks when the range is rejected.
+        let mut candidates = BTreeSet::new();
+        match filter()
+            .from_block(from_block)
+            .to_block(latest_block)
+            .query()
+            .await
+        {
+            Ok(events) => candidates.extend(events.into_iter().map(|event| event.token_id)),
+            Err(_) => {
+                let mut start_block = from_block;
+                while start_block <= latest_block {
+                    let end_block =
+                        (start_block + NFT_TRANSFER_LOG_BLOCK_RANGE - 1).min(latest_block);
+                    let events = filter()
+                        .from_block(start_block)
+                        .to_block(end_block)
+                        .query()
+                        .await?;
+                    candidates.extend(events.into_iter().map(|event| event.token_id));
+                    start_block = end_block + 1;
+                }
+            }
+        }
+
+        if candidates.len() > ERC721_TRANSFER_CANDIDATES_MAX {
+            return Err(format!(
+                "address received over {} tokens",
+                ERC721_TRANSFER_CANDIDATES_MAX
+            )
+            .into());
+        }
+        let candidates: Vec<U256> = candidates.into_iter().collect();
+        let mut multicall = multicall_at_block(
+            Multicall::new(self.contract.client(), None).await?,
+            self.block_number,
+        );
+        let mut token_ids = vec![];
+        for chunk in candidates.chunks(MULTICALL_BATCH_SIZE) {
+            multicall.clear_calls();
+            chunk.iter().for_each(|token_id| {
+                multicall.add_call(self.contract.owner_of(*token_id), true);
+            });
+            // burned tokens revert and are left out.
+            for (token_id, token) in chunk.iter().zip(multicall.call_raw().await?) {
+                if multicall_value(token).into_address() == Some(public_address) {
+                    token_ids.push(*token_id);
+                }
+            }
+        }
+        Ok(token_ids)
+    }
+
+    // get metadata of tokens concurrently, a failed fetch leaves the metadata empty.
+    async fn get_tokens(&self, token_ids: &[U256]) -> Vec<NFTMetadataItem> {
+        let semaphore = Arc::new(Semaphore::new(NFT_METADATA_FETCH_CONCURRENCY));
+        let handles: Vec<_> = token_ids
+            .iter()
+            .map(|token_id| {
+                let service = self.clone();
+                let semaphore = semaphore.clone();
+                let token_id = *token_id;
+                tokio::spawn(async move {
+                    let _permit = semaphore.acquire().await;
+                    service.get_metadata(&token_id).await.unwrap_or_default()
+                })
+            })
+            .collect();
+
+        let mut tokens = vec![];
+        for (token_id, handle) in token_ids.iter().zip(handles) {
+            tokens.push(NFTMetadataItem {
+                nft_id: token_id.to_string(),
+                metadata: handle.await.unwrap_or_default(),
+            });
+        }
+        tokens
+    }
+}
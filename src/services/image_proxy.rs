@@ -0,0 +1,207 @@
+use std::{env, io::Cursor, path::PathBuf};
+
+use ethers::{types::U256, utils::hex};
+use image::{DynamicImage, ImageOutputFormat};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    common::{
+        defines::{
+            Error, NetworkType, DEFAULT_IMAGE_CACHE_DIR, DEFAULT_IMAGE_THUMBNAIL_SIZES,
+            IMAGE_SOURCE_MAX_BYTES,
+        },
+        uri::fetch_uri_bytes,
+    },
+    services::namiland_erc1155::NamiLandERC1155Service,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThumbnailFormat {
+    Png,
+    WebP,
+}
+
+impl ThumbnailFormat {
+    // parse image format from query string, default is webp.
+    pub fn parse(value: Option<&str>) -> Option<ThumbnailFormat> {
+        match value {
+            None | Some("webp") => Some(ThumbnailFormat::WebP),
+            Some("png") => Some(ThumbnailFormat::Png),
+            Some(_) => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ThumbnailFormat::Png => "image/png",
+            ThumbnailFormat::WebP => "image/webp",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            ThumbnailFormat::Png => "png",
+            ThumbnailFormat::WebP => "webp",
+        }
+    }
+}
+
+// thumbnail sizes from the comma separated `IMAGE_THUMBNAIL_SIZES` env, or the default ones.
+pub fn thumbnail_sizes() -> Vec<u32> {
+    let configured: Vec<u32> = env::var("IMAGE_THUMBNAIL_SIZES")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|size| size.trim().parse().ok())
+        .collect();
+    match configured.is_empty() {
+        true => DEFAULT_IMAGE_THUMBNAIL_SIZES.to_vec(),
+        false => configured,
+    }
+}
+
+// url of the proxied image, prefixed with the `PUBLIC_BASE_URL` env when it is set.
+pub fn proxied_image_url(chain_id: u8, nft_id: &str) -> String {
+    let base_url = env::var("PUBLIC_BASE_URL").unwrap_or_default();
+    format!(
+        "{}/v1/namiland-game-item-nft/image/{}/{}",
+        base_url.trim_end_matches('/'),
+        chain_id,
+        nft_id
+    )
+}
+
+// Images of nft metadata, the source image and every thumbnail are cached on local disk.
+pub struct ImageProxyService {
+    erc1155_service: NamiLandERC1155Service,
+    cache_dir: PathBuf,
+}
+
+impl ImageProxyService {
+    pub fn new(network: NetworkType) -> Result<ImageProxyService, Error> {
+        let cache_dir =
+            env::var("IMAGE_CACHE_DIR").unwrap_or_else(|_| DEFAULT_IMAGE_CACHE_DIR.to_string());
+        Ok(ImageProxyService {
+            erc1155_service: NamiLandERC1155Service::new(network)?,
+            cache_dir: PathBuf::from(cache_dir).join(network.to_string()),
+        })
+    }
+}
+
+impl ImageProxyService {
+    // get the image of a nft, resized to fit in `size` pixels when it is given.
+    pub async fn get_image(
+        &self,
+        nft_id: &U256,
+        size: Option<u32>,
+        format: ThumbnailFormat,
+    ) -> Result<Vec<u8>, Error> {
+        if let Some(size) = size {
+            if !thumbnail_sizes().contains(&size) {
+                return Err(format!("thumbnail size {} is not supported", size).into());
+            }
+        }
+
+        let metadata = self.erc1155_service.get_metadata_by_nft_id(nft_id).await?;
+        if metadata.image.is_empty() {
+            return Err("nft metadata has no image".into());
+        }
+
+        // images are cached by the hash of image url, a changed url will be fetched again.
+        let nft_dir = self
+            .cache_dir
+            .join(nft_id.to_string())
+            .join(image_cache_key(&metadata.image));
+        let size_name = size.map_or("original".to_string(), |size| size.to_string());
+        let image_path = nft_dir.join(format!("{}.{}", size_name, format.extension()));
+        if let Ok(image) = tokio::fs::read(&image_path).await {
+            return Ok(image);
+        }
+
+        let source_path = nft_dir.join("source");
+        let source = match tokio::fs::read(&source_path).await {
+            Ok(source) => source,
+            Err(_) => {
+                let source = fetch_uri_bytes(&metadata.image, IMAGE_SOURCE_MAX_BYTES).await?;
+                tokio::fs::create_dir_all(&nft_dir).await?;
+                tokio::fs::write(&source_path, &source).await?;
+                source
+            }
+        };
+
+        let image =
+            tokio::task::spawn_blocking(move || encode_image(&source, size, format)).await??;
+        tokio::fs::create_dir_all(&nft_dir).await?;
+        tokio::fs::write(&image_path, &image).await?;
+        Ok(image)
+    }
+}
+
+// directory name of an image url, a sha256 digest stays the same across toolchains.
+fn image_cache_key(image_url: &str) -> String {
+    hex::encode(&Sha256::digest(image_url.as_bytes())[..16])
+}
+
+fn encode_image(
+    source: &[u8],
+    size: Option<u32>,
+    format: ThumbnailFormat,
+) -> Result<Vec<u8>, Error> {
+    let mut image = image::load_from_memory(source)?;
+    if let Some(size) = size {
+        image = image.thumbnail(size, size);
+    }
+    // webp encoder only accepts 8 bit color.
+    let image = DynamicImage::ImageRgba8(image.to_rgba8());
+
+    let output_format = match format {
+        ThumbnailFormat::Png => ImageOutputFormat::Png,
+        ThumbnailFormat::WebP => ImageOutputFormat::WebP,
+    };
+    let mut output = Cursor::new(vec![]);
+    image.write_to(&mut output, output_format)?;
+    Ok(output.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{DynamicImage, ImageOutputFormat, RgbaImage};
+
+    use crate::{
+        common::uri::fetch_uri_bytes,
+        services::image_proxy::{encode_image, image_cache_key, ThumbnailFormat},
+    };
+
+    #[test]
+    fn test_image_cache_key() {
+        // the key of existing cache directories must not change.
+        assert_eq!(image_cache_key(""), "e3b0c44298fc1c149afbf4c8996fb924");
+        assert_ne!(
+            image_cache_key("ipfs://QmImage/10001.png"),
+            image_cache_key("ipfs://QmImage/10002.png")
+        );
+    }
+
+    #[test]
+    fn test_encode_thumbnail() {
+        let mut source = Cursor::new(vec![]);
+        DynamicImage::ImageRgba8(RgbaImage::new(300, 150))
+            .write_to(&mut source, ImageOutputFormat::Png)
+            .unwrap();
+        let thumbnail = encode_image(source.get_ref(), Some(64), ThumbnailFormat::Png).unwrap();
+        let thumbnail = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (64, 32));
+
+        assert!(encode_image(b"not an image", None, ThumbnailFormat::WebP).is_err());
+        assert_eq!(ThumbnailFormat::parse(None), Some(ThumbnailFormat::WebP));
+        assert_eq!(ThumbnailFormat::parse(Some("gif")), None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_size_limit() {
+        let uri = "data:image/png;base64,aGVsbG8gd29ybGQ=";
+        assert_eq!(fetch_uri_bytes(uri, 11).await.unwrap(), b"hello world");
+        assert!(fetch_uri_bytes(uri, 10).await.is_err());
+    }
+}
@@ -0,0 +1,231 @@
+use std::time::Instant;
+
+use ethers::{providers::Middleware, types::Address};
+
+use crate::{
+    common::{
+        address::{checksum_address, is_contract},
+        config::config,
+        defines::{Error, NetworkType, SUPPORTED_NETWORKS},
+        name_resolver::lookup_any_primary_name,
+        provider::ProviderManager,
+        tasks::spawn_all,
+    },
+    models::{ERC20Token, NamiXStakedInfo, NetworkInventory, WalletInventory},
+    services::{
+        erc20::ERC20Service, erc721::ERC721Service, namiland_erc1155::NamiLandERC1155Service,
+        neco_stake::NecoStakeService, price,
+    },
+};
+
+// Assets of one address on every network with a provider, queried concurrently.
+// Native and erc20 balances are valued in usd when `include_usd`.
+pub async fn get_wallet_inventory(public_address: Address, include_usd: bool) -> WalletInventory {
+    let started = Instant::now();
+    let handles: Vec<_> = SUPPORTED_NETWORKS
+        .into_iter()
+        .filter(|network| ProviderManager::instance().get_provider(*network).is_some())
+        .map(|network| {
+            (
+                network,
+                tokio::spawn(get_network_inventory(network, public_address, include_usd)),
+            )
+        })
+        .collect();
+    let primary_name = tokio::spawn(lookup_any_primary_name(public_address));
+
+    let mut networks = vec![];
+    for (network, handle) in handles {
+        networks.push(match handle.await {
+            Ok(inventory) => inventory,
+            Err(err) => NetworkInventory {
+                network,
+                chain_id: network.chain_id(),
+                status: "error".to_string(),
+                errors: vec![err.to_string()],
+                ..Default::default()
+            },
+        });
+    }
+
+    let total_usd_value = match include_usd {
+        true => Some(
+            networks
+                .iter()
+                .filter_map(|network| network.total_usd_value)
+                .sum(),
+        ),
+        false => None,
+    };
+    WalletInventory {
+        public_address: checksum_address(&public_address),
+        primary_name: primary_name.await.unwrap_or_default(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        networks,
+        total_usd_value,
+    }
+}
+
+async fn get_network_inventory(
+    network: NetworkType,
+    public_address: Address,
+    include_usd: bool,
+) -> NetworkInventory {
+    let started = Instant::now();
+    let (is_contract, native, erc20_tokens, erc1155_ownerships, erc721_ownerships, stake) = tokio::join!(
+        is_contract(network, public_address),
+        get_native_balance(network, public_address, include_usd),
+        spawn_all(
+            config()
+                .erc20_tokens
+                .iter()
+                .filter(|token| token.network == network)
+                .map(|token| {
+                    let token = token.clone();
+                    async move {
+                        let mut balance = ERC20Service::from_address(network, token.address)?
+                            .get_token(public_address)
+                            .await
+                            .map_err(|err| format!("erc20 {}: {}", token.symbol, err))?;
+                        if include_usd {
+                            price::value_token(network, token.address, &mut balance).await;
+                        }
+                        Ok(balance)
+                    }
+                }),
+        ),
+        spawn_all(
+            config()
+                .erc1155_collections
+                .iter()
+                .filter(|collection| collection.network == network)
+                .map(|collection| {
+                    let collection = collection.clone();
+                    async move {
+                        let id = collection.id.clone();
+                        NamiLandERC1155Service::from_collection(collection)?
+                            .get_nft_ownership(public_address)
+                            .await
+                            .map_err(|err| format!("erc1155 {}: {}", id, err).into())
+                    }
+                }),
+        ),
+        spawn_all(
+            config()
+                .erc721_collections
+                .iter()
+                .filter(|collection| collection.network == network)
+                .map(|collection| {
+                    let collection = collection.clone();
+                    async move {
+                        ERC721Service::new(network, collection.address)?
+                            .get_ownership(public_address, collection.start_block)
+                            .await
+                            .map_err(|err| format!("erc721 {}: {}", collection.id, err).into())
+                    }
+                }),
+        ),
+        get_stake(network, public_address),
+    );
+
+    let mut inventory = NetworkInventory {
+        network,
+        chain_id: network.chain_id(),
+        is_contract,
+        ..Default::default()
+    };
+    let mut succeeded = 0;
+    match native {
+        Ok(native) => {
+            inventory.native = Some(native);
+            succeeded += 1;
+        }
+        Err(err) => inventory.errors.push(format!("native: {}", err)),
+    }
+    for result in erc20_tokens {
+        match result {
+            Ok(token) => {
+                inventory.erc20_tokens.push(token);
+                succeeded += 1;
+            }
+            Err(err) => inventory.errors.push(err),
+        }
+    }
+    for result in erc1155_ownerships {
+        match result {
+            Ok(ownership) => {
+                inventory.erc1155_ownerships.push(ownership);
+                succeeded += 1;
+            }
+            Err(err) => inventory.errors.push(err),
+        }
+    }
+    for result in erc721_ownerships {
+        match result {
+            Ok(ownership) => {
+                inventory.erc721_ownerships.push(ownership);
+                succeeded += 1;
+            }
+            Err(err) => inventory.errors.push(err),
+        }
+    }
+    match stake {
+        Ok(stake) => {
+            succeeded += stake.is_some() as usize;
+            inventory.stake = stake;
+        }
+        Err(err) => inventory.errors.push(format!("stake: {}", err)),
+    }
+
+    if include_usd {
+        let mut total_usd_value = 0.0;
+        for token in inventory.native.iter().chain(inventory.erc20_tokens.iter()) {
+            total_usd_value += token.usd_value.unwrap_or_default();
+        }
+        inventory.total_usd_value = Some(total_usd_value);
+    }
+
+    inventory.status = match (inventory.errors.is_empty(), succeeded) {
+        (true, _) => "ok",
+        (false, 0) => "error",
+        (false, _) => "partial",
+    }
+    .to_string();
+    inventory.elapsed_ms = started.elapsed().as_millis() as u64;
+    inventory
+}
+
+async fn get_native_balance(
+    network: NetworkType,
+    public_address: Address,
+    include_usd: bool,
+) -> Result<ERC20Token, Error> {
+    let client = match ProviderManager::instance().get_provider(network) {
+        Some(client) => client,
+        None => return Err("get provider failed".into()),
+    };
+    let balance = client.get_balance(public_address, None).await?;
+    let mut native = ERC20Token {
+        symbol: network.native_symbol().to_string(),
+        decimal: 18,
+        amount: balance.to_string(),
+        ..Default::default()
+    };
+    // native tokens are priced by their wrapped tokens.
+    if let (true, Some(wrapped)) = (include_usd, network.wrapped_native_token()) {
+        price::value_token(network, wrapped, &mut native).await;
+    }
+    Ok(native)
+}
+
+// nothing is staked on networks without the stake contract.
+async fn get_stake(
+    network: NetworkType,
+    public_address: Address,
+) -> Result<Option<NamiXStakedInfo>, Error> {
+    let stake_service = match NecoStakeService::new(network) {
+        Ok(stake_service) => stake_service,
+        Err(_) => return Ok(None),
+    };
+    Ok(Some(stake_service.get_staked_info(public_address).await?))
+}
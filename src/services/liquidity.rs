@@ -0,0 +1,117 @@
+use ethers::types::{Address, U256, U512};
+
+use crate::{
+    common::{
+        address::checksum_address, config::LiquidityPairConfig, defines::Error, finality::at_block,
+        provider::ProviderManager,
+    },
+    models::{ERC20Token, LPPosition},
+    services::{erc20::ERC20Service, price, price::PancakePairContract},
+};
+
+// Position of an address in a liquidity pair: lp token balance, share of the pool and
+// the amounts of both tokens redeemable by the balance, read at the block if given.
+pub async fn get_lp_position(
+    pair: &LiquidityPairConfig,
+    public_address: Address,
+    include_usd: bool,
+    block_number: Option<u64>,
+) -> Result<LPPosition, Error> {
+    let client = match ProviderManager::instance().get_provider(pair.network) {
+        Some(client) => client,
+        None => return Err("get provider failed".into()),
+    };
+    let contract = PancakePairContract::new(pair.address, client);
+    let lp_service = ERC20Service::from_address(pair.network, pair.address)?.at_block(block_number);
+    let reserves_call = at_block(contract.get_reserves(), block_number);
+    let token0_call = at_block(contract.token_0(), block_number);
+    let token1_call = at_block(contract.token_1(), block_number);
+    let ((reserve0, reserve1, _), token0, token1, lp_token, total_supply) = tokio::try_join!(
+        async { Ok::<_, Error>(reserves_call.call().await?) },
+        async { Ok(token0_call.call().await?) },
+        async { Ok(token1_call.call().await?) },
+        lp_service.get_token(public_address),
+        lp_service.get_total_supply()
+    )?;
+
+    let balance = U256::from_dec_str(&lp_token.amount)?;
+    let mut underlying_tokens = vec![];
+    for (token, reserve) in [(token0, reserve0), (token1, reserve1)] {
+        let token_service = ERC20Service::from_address(pair.network, token)?.at_block(block_number);
+        let (symbol, decimal) =
+            tokio::try_join!(token_service.get_symbol(), token_service.get_decimal())?;
+        let mut underlying = ERC20Token {
+            symbol,
+            decimal,
+            amount: underlying_amount(U256::from(reserve), balance, total_supply).to_string(),
+            ..Default::default()
+        };
+        if include_usd {
+            price::value_token(pair.network, token, &mut underlying).await;
+        }
+        underlying_tokens.push(underlying);
+    }
+
+    let total_usd_value = match include_usd {
+        true => Some(
+            underlying_tokens
+                .iter()
+                .filter_map(|token| token.usd_value)
+                .sum(),
+        ),
+        false => None,
+    };
+    Ok(LPPosition {
+        public_address: checksum_address(&public_address),
+        network: pair.network,
+        pair_id: pair.id.clone(),
+        pair_address: checksum_address(&pair.address),
+        lp_token,
+        total_supply: total_supply.to_string(),
+        share_of_pool: pool_share(balance, total_supply),
+        underlying_tokens,
+        total_usd_value,
+    })
+}
+
+// amount of a reserve redeemable by the lp token balance.
+pub fn underlying_amount(reserve: U256, balance: U256, total_supply: U256) -> U256 {
+    if total_supply.is_zero() {
+        return U256::zero();
+    }
+    U256::try_from(reserve.full_mul(balance) / U512::from(total_supply)).unwrap_or_default()
+}
+
+// share of the pool owned by the lp token balance, from 0 to 1.
+pub fn pool_share(balance: U256, total_supply: U256) -> f64 {
+    if total_supply.is_zero() {
+        return 0.0;
+    }
+    // scale to 1e18 before converting to float to keep the precision.
+    let scaled = balance.full_mul(U256::exp10(18)) / U512::from(total_supply);
+    U256::try_from(scaled).unwrap_or_default().low_u128() as f64 / 1e18
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::U256;
+
+    use crate::services::liquidity::{pool_share, underlying_amount};
+
+    #[test]
+    fn test_lp_position_amounts() {
+        let total_supply = U256::exp10(20);
+        let balance = U256::exp10(18);
+        assert!((pool_share(balance, total_supply) - 0.01).abs() < 1e-12);
+        assert_eq!(
+            underlying_amount(U256::from(5_000_000u64), balance, total_supply),
+            U256::from(50_000u64)
+        );
+
+        assert_eq!(pool_share(balance, U256::zero()), 0.0);
+        assert_eq!(
+            underlying_amount(U256::from(1), balance, U256::zero()),
+            U256::zero()
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::{BTreeMap, HashMap};
+
+use ethers::types::{Address, U256};
+
+use crate::{
+    common::{
+        address::{checksum_address, lookup_address_details},
+        defines::NetworkType,
+        tasks::spawn_all,
+    },
+    models::{
+        AddressFailure, ERC20BalanceBatch, ERC20BalanceItem, NFTAmountTotal, NamiLandNFTOwnership,
+        NamiLandNFTOwnershipBatch, NamiXStakedInfo, NamiXStakedInfoBatch,
+    },
+    services::{
+        erc20::ERC20Service, namiland_erc1155::NamiLandERC1155Service, neco_stake::NecoStakeService,
+    },
+};
+
+// Ownership of every address, with amounts of each nft id summed across addresses.
+pub async fn get_nft_ownership_batch(
+    service: &NamiLandERC1155Service,
+    public_addresses: &[Address],
+) -> NamiLandNFTOwnershipBatch {
+    let results = spawn_all(public_addresses.iter().map(|public_address| {
+        let service = service.clone();
+        let public_address = *public_address;
+        async move { service.get_nft_ownership(public_address).await }
+    }))
+    .await;
+    let mut details = lookup_details(service.network, public_addresses).await;
+
+    let mut batch = NamiLandNFTOwnershipBatch {
+        network: service.network,
+        contract_address: checksum_address(&service.collection.address),
+        block_number: service.block_number,
+        ..Default::default()
+    };
+    for (public_address, result) in public_addresses.iter().zip(results) {
+        match result {
+            Ok(mut ownership) => {
+                (ownership.primary_name, ownership.is_contract) =
+                    details.remove(public_address).unwrap_or_default();
+                batch.ownerships.push(ownership);
+            }
+            Err(error) => batch.failures.push(address_failure(public_address, error)),
+        }
+    }
+    batch.totals = sum_nft_amounts(&batch.ownerships);
+    batch
+}
+
+// Balance of every address and the total across addresses.
+pub async fn get_erc20_balance_batch(
+    service: &ERC20Service,
+    network: NetworkType,
+    public_addresses: &[Address],
+) -> ERC20BalanceBatch {
+    let symbol = service
+        .get_symbol()
+        .await
+        .unwrap_or_else(|_| "unknown".to_string());
+    let decimal = service.get_decimal().await.unwrap_or(0);
+    let results = spawn_all(public_addresses.iter().map(|public_address| {
+        let service = service.clone();
+        let account = checksum_address(public_address);
+        async move { service.get_balance(&account).await }
+    }))
+    .await;
+    let mut details = lookup_details(network, public_addresses).await;
+
+    let mut batch = ERC20BalanceBatch {
+        symbol,
+        decimal,
+        block_number: service.block_number(),
+        ..Default::default()
+    };
+    let mut total_amount = U256::zero();
+    for (public_address, result) in public_addresses.iter().zip(results) {
+        match result {
+            Ok(amount) => {
+                let (primary_name, is_contract) =
+                    details.remove(public_address).unwrap_or_default();
+                total_amount = total_amount.saturating_add(amount);
+                batch.balances.push(ERC20BalanceItem {
+                    public_address: checksum_address(public_address),
+                    primary_name,
+                    is_contract,
+                    amount: amount.to_string(),
+                    usd_value: None,
+                });
+            }
+            Err(error) => batch.failures.push(address_failure(public_address, error)),
+        }
+    }
+    batch.total_amount = total_amount.to_string();
+    batch
+}
+
+// Stake info of every address and the total staked amount across addresses.
+// nothing is staked on networks without the stake contract.
+pub async fn get_neco_staked_batch(
+    network: NetworkType,
+    public_addresses: &[Address],
+    block_number: Option<u64>,
+) -> NamiXStakedInfoBatch {
+    let mut batch = NamiXStakedInfoBatch {
+        block_number,
+        ..Default::default()
+    };
+    let stake_service = match NecoStakeService::new(network) {
+        Ok(stake_service) => stake_service.at_block(block_number),
+        Err(_) => {
+            batch.stakes = public_addresses
+                .iter()
+                .map(|public_address| NamiXStakedInfo {
+                    public_address: checksum_address(public_address),
+                    primary_name: None,
+                    is_contract: None,
+                    staked_amount: "0".to_string(),
+                    staked_time: "0".to_string(),
+                    block_number,
+                })
+                .collect();
+            batch.total_staked_amount = "0".to_string();
+            return batch;
+        }
+    };
+    let results = spawn_all(public_addresses.iter().map(|public_address| {
+        let stake_service = stake_service.clone();
+        let public_address = *public_address;
+        async move { stake_service.get_staked_info(public_address).await }
+    }))
+    .await;
+    let mut details = lookup_details(network, public_addresses).await;
+
+    let mut total_staked_amount = U256::zero();
+    for (public_address, result) in public_addresses.iter().zip(results) {
+        match result {
+            Ok(mut stake) => {
+                (stake.primary_name, stake.is_contract) =
+                    details.remove(public_address).unwrap_or_default();
+                let staked_amount = U256::from_dec_str(&stake.staked_amount).unwrap_or_default();
+                total_staked_amount = total_staked_amount.saturating_add(staked_amount);
+                batch.stakes.push(stake);
+            }
+            Err(error) => batch.failures.push(address_failure(public_address, error)),
+        }
+    }
+    batch.total_staked_amount = total_staked_amount.to_string();
+    batch
+}
+
+// sum the amount of every nft id across ownerships, ordered by nft id.
+pub fn sum_nft_amounts(ownerships: &[NamiLandNFTOwnership]) -> Vec<NFTAmountTotal> {
+    let mut totals: BTreeMap<U256, u64> = BTreeMap::new();
+    for item in ownerships
+        .iter()
+        .flat_map(|ownership| &ownership.ownerships)
+    {
+        let nft_id = U256::from_dec_str(&item.nft_id).unwrap_or_default();
+        let total = totals.entry(nft_id).or_default();
+        *total = total.saturating_add(item.amount);
+    }
+    totals
+        .into_iter()
+        .map(|(nft_id, amount)| NFTAmountTotal {
+            nft_id: nft_id.to_string(),
+            amount,
+        })
+        .collect()
+}
+
+// look up primary names and contract flags of the addresses concurrently.
+async fn lookup_details(
+    network: NetworkType,
+    public_addresses: &[Address],
+) -> HashMap<Address, (Option<String>, Option<bool>)> {
+    let details = spawn_all(public_addresses.iter().map(|public_address| {
+        let public_address = *public_address;
+        async move { Ok(lookup_address_details(network, public_address).await) }
+    }))
+    .await;
+    public_addresses
+        .iter()
+        .zip(details)
+        .filter_map(|(public_address, details)| Some((*public_address, details.ok()?)))
+        .collect()
+}
+
+fn address_failure(public_address: &Address, error: String) -> AddressFailure {
+    AddressFailure {
+        public_address: checksum_address(public_address),
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        models::{NamiLandNFTOwnership, OwnershipItem},
+        services::multi_address::sum_nft_amounts,
+    };
+
+    fn ownership(items: &[(&str, u64)]) -> NamiLandNFTOwnership {
+        NamiLandNFTOwnership {
+            ownerships: items
+                .iter()
+                .map(|(nft_id, amount)| OwnershipItem {
+                    nft_id: nft_id.to_string(),
+                    amount: *amount,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sum_nft_amounts() {
+        let totals = sum_nft_amounts(&[
+            ownership(&[("13005", 2), ("9", 1)]),
+            ownership(&[("13005", 3)]),
+        ]);
+        let totals: Vec<(String, u64)> = totals
+            .into_iter()
+            .map(|total| (total.nft_id, total.amount))
+            .collect();
+        assert_eq!(totals, vec![("9".to_string(), 1), ("13005".to_string(), 5)]);
+    }
+}
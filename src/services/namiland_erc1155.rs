@@ -1,20 +1,39 @@
+use super::asset_events::{get_erc1155_transfers, AssetTransfer};
+use super::nft_metadata::{
+    get_cached_metadata, invalidate_cached_metadata, refresh_cached_metadata, MetadataKey,
+};
 use crate::{
     common::{
-        address::get_contract_address,
+        address::checksum_address,
+        config::{config, ERC1155CollectionConfig, TokenIdSource},
         defines::{
-            Error, GameClient, NetworkType, SupportedContractType, NAMILAND_GAME_ITEM_NFT_IDS,
+            Error, NetworkType, NAMILAND_GAME_ITEM_COLLECTION_DESCRIPTION,
+            NFT_METADATA_FETCH_CONCURRENCY, NFT_TRANSFER_LOG_BLOCK_RANGE,
         },
+        finality::{at_block, multicall_at_block},
+        multicall::{multicall_uints, multicall_value},
+        pagination::{paginate, SortOrder},
         provider::ProviderManager,
     },
-    models::{NamiLandERC1155NFTMetadata, NamiLandNFTOwnership, OwnershipItem},
+    models::{
+        CollectionToken, ContractURIMetadata, NFTHolder, NFTSupply, NamiLandERC1155NFTMetadata,
+        NamiLandLockedNFTs, NamiLandNFTCollection, NamiLandNFTHolders, NamiLandNFTOwnership,
+        NamiLandNFTPermission, OwnershipItem,
+    },
 };
 use ethers::{
-    prelude::{abigen, Lazy},
-    providers::{Http, Provider},
+    abi::Token,
+    prelude::{abigen, Lazy, Multicall},
+    providers::{Http, Middleware, Provider},
     types::{Address, U256},
 };
-use std::{borrow::Borrow, collections::HashMap, sync::Arc};
-use tokio::sync::{mpsc, Mutex};
+use log::warn;
+use std::{
+    borrow::Borrow,
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
+use tokio::sync::{mpsc, Mutex, Semaphore};
 
 abigen!(
     NamiLandERC1155Contract,
@@ -22,25 +41,110 @@ abigen!(
     event_derives(serde::Deserialize, serde::Serialize)
 );
 
-static NFT_URL_CACHES: Lazy<Mutex<HashMap<U256, String>>> =
-    Lazy::new(|| Mutex::new(HashMap::new()));
-static NFT_METADATA_CACHES: Lazy<Mutex<HashMap<U256, NamiLandERC1155NFTMetadata>>> =
+// The global lock is only held to look up the cache of a collection, the scan of a
+// collection is guarded by its own `scanning` lock.
+static NFT_HOLDER_CACHES: Lazy<Mutex<HashMap<(NetworkType, Address), HolderCache>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+#[derive(Debug, Default)]
+struct HolderCache {
+    // taken out while the collection is scanned.
+    snapshot: Arc<HolderSnapshot>,
+    // one scan of the collection at a time, others wait for its result.
+    scanning: Arc<Mutex<()>>,
+}
+
+// Balances of every holder rebuilt from transfer events, scanned incrementally.
+#[derive(Debug, Clone, Default)]
+struct HolderSnapshot {
+    next_block: u64,
+    balances: HashMap<U256, HashMap<Address, U256>>,
+}
+
+impl HolderSnapshot {
+    // apply the transfers of a scanned range in chain order, balances are clamped at zero
+    // so a transfer applied before the mint it depends on would be lost.
+    fn apply_range(&mut self, mut transfers: Vec<AssetTransfer>, to_block: u64) {
+        transfers.sort_by_key(|transfer| (transfer.block_number, transfer.log_index));
+        for transfer in transfers {
+            for (id, value) in transfer.nft_ids.iter().zip(transfer.amounts.iter()) {
+                self.apply_transfer(transfer.from, transfer.to, *id, *value);
+            }
+        }
+        self.next_block = to_block + 1;
+    }
+
+    fn apply_transfer(&mut self, from: Address, to: Address, id: U256, value: U256) {
+        let holders = self.balances.entry(id).or_default();
+        if !from.is_zero() {
+            let balance = holders.entry(from).or_default();
+            *balance = balance.saturating_sub(value);
+            if balance.is_zero() {
+                holders.remove(&from);
+            }
+        }
+        if !to.is_zero() {
+            *holders.entry(to).or_default() += value;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HolderSortField {
+    Amount,
+    Address,
+}
+
+impl HolderSortField {
+    // parse sort field from query string, default is amount.
+    pub fn parse(value: Option<&str>) -> Option<HolderSortField> {
+        match value {
+            None | Some("amount") => Some(HolderSortField::Amount),
+            Some("address") => Some(HolderSortField::Address),
+            Some(_) => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct NamiLandERC1155Service {
     pub contract: NamiLandERC1155Contract<Provider<Http>>,
+    pub network: NetworkType,
+    pub collection: ERC1155CollectionConfig,
+    // ownership is read at the block resolved from the `finality` param, or the latest block.
+    pub block_number: Option<u64>,
 }
 
 impl NamiLandERC1155Service {
+    // service of the default collection on the network.
     pub fn new(network: NetworkType) -> Result<NamiLandERC1155Service, Error> {
+        let collection = match config().default_collection(network) {
+            Some(collection) => collection,
+            None => return Err("no collection is configured on the network".into()),
+        };
+        NamiLandERC1155Service::from_collection(collection.clone())
+    }
+
+    pub fn from_collection(
+        collection: ERC1155CollectionConfig,
+    ) -> Result<NamiLandERC1155Service, Error> {
+        let network = collection.network;
         let client = match ProviderManager::instance().get_provider(network) {
             Some(client) => client,
             None => return Err("get provider failed".into()),
         };
-        let address = get_contract_address(SupportedContractType::NamiLandGameItemNFT, network)?;
-        let contract = NamiLandERC1155Contract::new(address, client.clone());
-        Ok(NamiLandERC1155Service { contract })
+        let contract = NamiLandERC1155Contract::new(collection.address, client.clone());
+        Ok(NamiLandERC1155Service {
+            contract,
+            network,
+            collection,
+            block_number: None,
+        })
+    }
+
+    pub fn at_block(mut self, block_number: Option<u64>) -> NamiLandERC1155Service {
+        self.block_number = block_number;
+        self
     }
 }
 
@@ -48,21 +152,17 @@ impl NamiLandERC1155Service {
     pub async fn get_nft_ownership(
         &self,
         public_address: Address,
-        game_client: GameClient,
-        network: NetworkType,
     ) -> Result<NamiLandNFTOwnership, Error> {
-        let ownership_items = self
-            .get_ownership_items(public_address, game_client)
-            .await?;
-
-        let contract_address =
-            get_contract_address(SupportedContractType::NamiLandGameItemNFT, network)?.to_string();
+        let ownership_items = self.get_ownership_items(public_address).await?;
 
         Ok(NamiLandNFTOwnership {
-            public_address: format!("{:?}", public_address),
-            network: network.to_owned(),
-            contract_address,
+            public_address: checksum_address(&public_address),
+            primary_name: None,
+            is_contract: None,
+            network: self.network,
+            contract_address: checksum_address(&self.contract.address()),
             ownerships: ownership_items,
+            block_number: self.block_number,
         })
     }
 
@@ -70,49 +170,54 @@ impl NamiLandERC1155Service {
     pub async fn get_ownership_items(
         &self,
         public_address: Address,
-        game_client: GameClient,
     ) -> Result<Vec<OwnershipItem>, Error> {
         let neco_nft = Arc::new(self.clone());
         let (tx, mut rx) = mpsc::channel(4096);
+        let locked_nft_ids = Arc::new(self.get_locked_nft_ids().await?);
 
-        let nft_ids: Vec<i32> = match game_client {
-            GameClient::NamiLand => {
-                let mut ids: Vec<i32> = vec![];
-                NAMILAND_GAME_ITEM_NFT_IDS
-                    .iter()
-                    .for_each(|id| ids.push(*id));
-                ids
-            }
-        };
+        let nft_ids = self.get_known_nft_ids().await;
 
-        nft_ids.clone().into_iter().for_each(|id: i32| {
+        nft_ids.clone().into_iter().for_each(|id: U256| {
             let neco_nft_copy = neco_nft.clone();
             let tx_copy = tx.clone();
+            let locked_nft_ids_copy = locked_nft_ids.clone();
 
             tokio::spawn(async move {
-                let balance = (*neco_nft_copy)
-                    .borrow()
-                    .contract
-                    .balance_of(public_address.clone(), U256::from(id))
-                    .call()
-                    .await
-                    .unwrap_or_default();
+                let balance = match at_block(
+                    (*neco_nft_copy)
+                        .borrow()
+                        .contract
+                        .balance_of(public_address.clone(), id),
+                    neco_nft_copy.block_number,
+                )
+                .call()
+                .await
+                {
+                    Ok(balance) => balance,
+                    // a read pinned to a block failing is not reported as not owned.
+                    Err(err) if neco_nft_copy.block_number.is_some() => {
+                        let _ = tx_copy.send(Err(err.to_string())).await;
+                        return;
+                    }
+                    Err(_) => U256::zero(),
+                };
 
                 let metadata = match balance.as_u64() {
                     0 => NamiLandERC1155NFTMetadata::default(),
                     _ => (*neco_nft_copy)
                         .borrow()
-                        .get_metadata_by_nft_id(&U256::from(id))
+                        .get_metadata_by_nft_id(&id)
                         .await
                         .unwrap_or_else(|_| NamiLandERC1155NFTMetadata::default()),
                 };
 
                 tx_copy
-                    .send(OwnershipItem {
+                    .send(Ok(OwnershipItem {
                         nft_id: id.to_string(),
                         amount: balance.as_u64(),
+                        locked: locked_nft_ids_copy.contains(&id),
                         nft_metadata: metadata,
-                    })
+                    }))
                     .await
                     .expect("TODO: panic message");
             });
@@ -120,67 +225,481 @@ impl NamiLandERC1155Service {
 
         let mut ownership_items: Vec<OwnershipItem> = vec![];
         for _ in 0..nft_ids.len() {
-            if let Some(ownership_item) = rx.recv().await {
-                if ownership_item.amount != 0 {
-                    ownership_items.push(ownership_item);
+            match rx.recv().await {
+                Some(Ok(ownership_item)) if ownership_item.amount != 0 => {
+                    ownership_items.push(ownership_item)
                 }
+                Some(Err(err)) => return Err(err.into()),
+                _ => {}
             }
         }
-        ownership_items.sort_by(|a, b| a.nft_id.cmp(&b.nft_id));
+        ownership_items.sort_by_key(|item| U256::from_dec_str(&item.nft_id).unwrap_or_default());
         Ok(ownership_items)
     }
 
+    // get metadata of many nft ids concurrently, with a limit of concurrent fetches.
+    pub async fn get_metadata_batch(
+        &self,
+        nft_ids: &[U256],
+    ) -> Vec<(U256, Result<NamiLandERC1155NFTMetadata, String>)> {
+        let semaphore = Arc::new(Semaphore::new(NFT_METADATA_FETCH_CONCURRENCY));
+        let handles: Vec<_> = nft_ids
+            .iter()
+            .map(|id| {
+                let service = self.clone();
+                let semaphore = semaphore.clone();
+                let id = *id;
+                tokio::spawn(async move {
+                    let _permit = semaphore.acquire().await;
+                    service
+                        .get_metadata_by_nft_id(&id)
+                        .await
+                        .map_err(|err| err.to_string())
+                })
+            })
+            .collect();
+
+        let mut results = vec![];
+        for (id, handle) in nft_ids.iter().zip(handles) {
+            let result = match handle.await {
+                Ok(result) => result,
+                Err(err) => Err(err.to_string()),
+            };
+            results.push((*id, result));
+        }
+        results
+    }
+
+    // get all locked nft ids, locked nft can not be transferred.
+    pub async fn get_locked_nft_ids(&self) -> Result<Vec<U256>, Error> {
+        // collections configured without token locking have no locked ids.
+        if !self.collection.token_locking {
+            return Ok(vec![]);
+        }
+        let block_number = self.block_number;
+        let length = at_block(self.contract.get_locked_token_ids_length(), block_number)
+            .call()
+            .await?;
+        let handles: Vec<_> = (0..length.as_u64())
+            .map(|index| {
+                let contract = self.contract.clone();
+                tokio::spawn(async move {
+                    at_block(
+                        contract.get_locked_token_ids_by_index(U256::from(index)),
+                        block_number,
+                    )
+                    .call()
+                    .await
+                })
+            })
+            .collect();
+
+        let mut nft_ids = vec![];
+        for handle in handles {
+            nft_ids.push(handle.await??);
+        }
+        nft_ids.sort();
+        Ok(nft_ids)
+    }
+
+    pub async fn get_locked_nfts(&self, network: NetworkType) -> Result<NamiLandLockedNFTs, Error> {
+        let nft_ids = self.get_locked_nft_ids().await?;
+        let contract_address = checksum_address(&self.contract.address());
+
+        Ok(NamiLandLockedNFTs {
+            network,
+            contract_address,
+            nft_ids: nft_ids.iter().map(|id| id.to_string()).collect(),
+        })
+    }
+
+    // get transfer whitelist, minter, owner and operator approval status of an address.
+    pub async fn get_nft_permission(
+        &self,
+        public_address: Address,
+        operator: Option<Address>,
+        network: NetworkType,
+    ) -> Result<NamiLandNFTPermission, Error> {
+        let owner_call = at_block(self.contract.owner(), self.block_number);
+        let minter_call = at_block(self.contract.minters(public_address), self.block_number);
+        let whitelist_call = at_block(
+            self.contract.transfer_whitelist(public_address),
+            self.block_number,
+        );
+        let (owner, is_minter, is_transfer_whitelisted) =
+            tokio::try_join!(owner_call.call(), minter_call.call(), whitelist_call.call())?;
+
+        let is_approved_for_all = match operator {
+            Some(operator) => Some(
+                at_block(
+                    self.contract.is_approved_for_all(public_address, operator),
+                    self.block_number,
+                )
+                .call()
+                .await?,
+            ),
+            None => None,
+        };
+
+        let contract_address = checksum_address(&self.contract.address());
+
+        Ok(NamiLandNFTPermission {
+            public_address: checksum_address(&public_address),
+            primary_name: None,
+            is_contract: None,
+            network,
+            contract_address,
+            is_owner: owner == public_address,
+            is_minter,
+            is_transfer_whitelisted,
+            operator: operator.map(|operator| checksum_address(&operator)),
+            is_approved_for_all,
+        })
+    }
+
     // get nft metadata by nft id
     pub async fn get_metadata_by_nft_id(
         &self,
         nft_id: &U256,
     ) -> Result<NamiLandERC1155NFTMetadata, Error> {
-        let map = NFT_METADATA_CACHES.lock().await;
-        let result = map.get(&nft_id).cloned();
-        drop(map);
+        get_cached_metadata(self.metadata_key(nft_id), self.load_uri(nft_id)).await
+    }
+
+    // fetch uri and metadata regardless of the cache. The cache is only updated on
+    // success, so a failed refresh keeps serving the cached metadata.
+    pub async fn refresh_metadata(&self, nft_id: &U256) -> Result<(), Error> {
+        let url = self.load_uri(nft_id).await?;
+        refresh_cached_metadata(self.metadata_key(nft_id), url).await
+    }
 
-        match result {
-            Some(metadata) => Ok(metadata),
+    // drop cached uri and metadata of one nft id, or of all nft ids, of every collection
+    // on the network.
+    pub async fn invalidate_metadata(network: NetworkType, nft_id: Option<U256>) -> usize {
+        let mut purged = 0;
+        for collection in &config().erc1155_collections {
+            if collection.network == network {
+                purged += invalidate_cached_metadata(network, collection.address, nft_id).await;
+            }
+        }
+        purged
+    }
+
+    fn metadata_key(&self, nft_id: &U256) -> MetadataKey {
+        (self.network, self.contract.address(), *nft_id)
+    }
+
+    async fn load_uri(&self, nft_id: &U256) -> Result<String, Error> {
+        match &self.collection.metadata_uri {
+            Some(metadata_uri) => Ok(metadata_uri.clone()),
+            None => Ok(self.contract.uri(*nft_id).call().await?),
+        }
+    }
+}
+
+impl NamiLandERC1155Service {
+    // get holders of one nft id or of the whole collection, with aggregate counts.
+    pub async fn get_nft_holders(
+        &self,
+        network: NetworkType,
+        nft_id: Option<U256>,
+        sort_by: HolderSortField,
+        order: SortOrder,
+        page: usize,
+        page_size: usize,
+    ) -> Result<NamiLandNFTHolders, Error> {
+        let (scanned_block, snapshot) = self.get_holder_balances(network).await?;
+
+        // merge balances of the requested ids by holder.
+        let mut merged: HashMap<Address, (U256, u64)> = HashMap::new();
+        snapshot
+            .balances
+            .iter()
+            .filter(|(id, _)| nft_id.is_none() || nft_id == Some(**id))
+            .flat_map(|(_, holders)| holders.iter())
+            .for_each(|(address, amount)| {
+                let entry = merged.entry(*address).or_default();
+                entry.0 += *amount;
+                entry.1 += 1;
+            });
+
+        let mut holders: Vec<(Address, U256, u64)> = merged
+            .into_iter()
+            .map(|(address, (amount, count))| (address, amount, count))
+            .collect();
+        holders.sort_by(|a, b| {
+            let ordering = match sort_by {
+                HolderSortField::Amount => a.1.cmp(&b.1).then(a.0.cmp(&b.0)),
+                HolderSortField::Address => a.0.cmp(&b.0),
+            };
+            order.apply(ordering)
+        });
+        let unique_holders = holders.len() as u64;
+
+        let nft_ids: Vec<U256> = match nft_id {
+            Some(nft_id) => vec![nft_id],
             None => {
-                // 1. get nft url
-                let url = self.get_nft_url(nft_id).await?;
-                let requester = reqwest::Client::new();
-                let result = requester.get(url).send().await?.text().await?;
-                let metadata: NamiLandERC1155NFTMetadata = serde_json::from_str(&result)?;
-                NFT_METADATA_CACHES
-                    .lock()
-                    .await
-                    .insert(nft_id.into(), metadata.to_owned());
-                Ok(metadata)
+                let mut ids: BTreeSet<U256> = snapshot.balances.keys().cloned().collect();
+                ids.extend(self.collection.token_ids.iter().map(|id| U256::from(*id)));
+                ids.into_iter().collect()
             }
+        };
+        let mut supplies = vec![];
+        for (id, total_supply) in self.get_total_supplies(&nft_ids).await? {
+            supplies.push(NFTSupply {
+                nft_id: id.to_string(),
+                total_supply: total_supply.to_string(),
+                holder_count: snapshot
+                    .balances
+                    .get(&id)
+                    .map_or(0, |holders| holders.len() as u64),
+            });
         }
+
+        let contract_address = checksum_address(&self.contract.address());
+
+        Ok(NamiLandNFTHolders {
+            network,
+            contract_address,
+            nft_id: nft_id.map(|id| id.to_string()),
+            unique_holders,
+            supplies,
+            holders: paginate(holders, page, page_size)
+                .into_iter()
+                .map(|(address, amount, count)| NFTHolder {
+                    public_address: checksum_address(&address),
+                    amount: amount.to_string(),
+                    nft_id_count: count,
+                })
+                .collect(),
+            page: page as u64,
+            page_size: page_size as u64,
+            scanned_block,
+        })
     }
 
-    // get nft uri.
-    pub async fn get_nft_url(&self, nft_id: &U256) -> Result<String, Error> {
-        let map = NFT_URL_CACHES.lock().await;
-        let result = map.get(&nft_id).cloned();
-        drop(map);
+    // get total supply of each nft id concurrently, in the order of nft ids.
+    pub async fn get_total_supplies(&self, nft_ids: &[U256]) -> Result<Vec<(U256, U256)>, Error> {
+        let handles: Vec<_> = nft_ids
+            .iter()
+            .map(|id| {
+                let contract = self.contract.clone();
+                let id = *id;
+                tokio::spawn(async move { contract.total_supply(id).call().await })
+            })
+            .collect();
+
+        let mut supplies = vec![];
+        for (id, handle) in nft_ids.iter().zip(handles) {
+            supplies.push((*id, handle.await??));
+        }
+        Ok(supplies)
+    }
 
-        match result {
-            Some(url) => Ok(url),
+    // scan transfer events since the last scanned block and return the balances of all holders.
+    async fn get_holder_balances(
+        &self,
+        network: NetworkType,
+    ) -> Result<(u64, Arc<HolderSnapshot>), Error> {
+        let start_block = match self.collection.start_block {
+            Some(start_block) => start_block,
             None => {
-                let url = self.contract.uri(nft_id.into()).call().await?;
-                NFT_URL_CACHES
-                    .lock()
-                    .await
-                    .insert(nft_id.into(), url.clone());
-                Ok(url)
+                return Err(format!(
+                    "start_block of collection {} is not configured",
+                    self.collection.id
+                )
+                .into())
             }
+        };
+        let key = (network, self.contract.address());
+        let scanning = NFT_HOLDER_CACHES
+            .lock()
+            .await
+            .entry(key)
+            .or_default()
+            .scanning
+            .clone();
+        let _scanning = scanning.lock().await;
+
+        let mut snapshot = match NFT_HOLDER_CACHES.lock().await.get_mut(&key) {
+            Some(cache) => std::mem::take(&mut cache.snapshot),
+            None => Arc::default(),
+        };
+        // the balances are only copied when a previous result is still in use.
+        let scanned = self
+            .scan_holders(Arc::make_mut(&mut snapshot), start_block)
+            .await;
+        // keep the ranges scanned before a failure.
+        if let Some(cache) = NFT_HOLDER_CACHES.lock().await.get_mut(&key) {
+            cache.snapshot = snapshot.clone();
+        }
+        Ok((scanned?, snapshot))
+    }
+
+    // scan the ranges up to the latest block into the snapshot, return the latest block.
+    async fn scan_holders(
+        &self,
+        snapshot: &mut HolderSnapshot,
+        start_block: u64,
+    ) -> Result<u64, Error> {
+        snapshot.next_block = snapshot.next_block.max(start_block);
+        let latest_block = self.contract.client().get_block_number().await?.as_u64();
+        while snapshot.next_block <= latest_block {
+            let to_block =
+                (snapshot.next_block + NFT_TRANSFER_LOG_BLOCK_RANGE - 1).min(latest_block);
+            // only apply the range once both queries succeeded.
+            let transfers = get_erc1155_transfers(
+                self.network,
+                self.contract.address(),
+                snapshot.next_block,
+                to_block,
+            )
+            .await?;
+            snapshot.apply_range(transfers, to_block);
         }
+        Ok(latest_block)
+    }
+}
+
+impl NamiLandERC1155Service {
+    // get token ids registered on chain, batched by multicall.
+    pub async fn get_token_ids(&self) -> Result<Vec<U256>, Error> {
+        let length = self.contract.get_token_ids_length().call().await?;
+        let indexes: Vec<U256> = (0..length.as_u64()).map(U256::from).collect();
+        let mut multicall = multicall_at_block(
+            Multicall::new(self.contract.client(), None).await?,
+            self.block_number,
+        );
+        multicall_uints(&mut multicall, &indexes, |index| {
+            self.contract.get_token_id_by_index(index)
+        })
+        .await
+    }
+
+    // get the configured nft ids of the collection together with the ids found by its
+    // token id source, falls back to the configured ids when the chain can not be queried.
+    pub async fn get_known_nft_ids(&self) -> Vec<U256> {
+        let mut nft_ids: BTreeSet<U256> = self
+            .collection
+            .token_ids
+            .iter()
+            .map(|id| U256::from(*id))
+            .collect();
+        let found = match self.collection.token_id_source {
+            TokenIdSource::Static => Ok(vec![]),
+            TokenIdSource::Contract => self.get_token_ids().await,
+            TokenIdSource::Events => self
+                .get_holder_balances(self.network)
+                .await
+                .map(|(_, snapshot)| snapshot.balances.keys().copied().collect()),
+        };
+        match found {
+            Ok(found) => nft_ids.extend(found),
+            Err(err) => warn!(
+                "get token ids of {} on {} failed: {}",
+                self.collection.id, self.network, err
+            ),
+        }
+        nft_ids.into_iter().collect()
+    }
+
+    // get contract level info and supply of every token id, batched by multicall.
+    pub async fn get_collection(
+        &self,
+        network: NetworkType,
+    ) -> Result<NamiLandNFTCollection, Error> {
+        let mut multicall = multicall_at_block(
+            Multicall::new(self.contract.client(), None).await?,
+            self.block_number,
+        );
+        multicall
+            .add_call(self.contract.name(), false)
+            .add_call(self.contract.symbol(), false)
+            .add_call(self.contract.base_token_uri(), false)
+            .add_call(self.contract.owner(), false)
+            .add_call(self.contract.get_token_ids_length(), false);
+        let mut tokens = multicall.call_raw().await?.into_iter().map(multicall_value);
+
+        let invalid = || "invalid multicall result";
+        let name = tokens
+            .next()
+            .and_then(Token::into_string)
+            .ok_or_else(invalid)?;
+        let symbol = tokens
+            .next()
+            .and_then(Token::into_string)
+            .ok_or_else(invalid)?;
+        let base_token_uri = tokens
+            .next()
+            .and_then(Token::into_string)
+            .ok_or_else(invalid)?;
+        let owner = tokens
+            .next()
+            .and_then(Token::into_address)
+            .ok_or_else(invalid)?;
+        let length = tokens
+            .next()
+            .and_then(Token::into_uint)
+            .ok_or_else(invalid)?;
+
+        let indexes: Vec<U256> = (0..length.as_u64()).map(U256::from).collect();
+        let nft_ids = multicall_uints(&mut multicall, &indexes, |index| {
+            self.contract.get_token_id_by_index(index)
+        })
+        .await?;
+        let total_supplies = multicall_uints(&mut multicall, &nft_ids, |id| {
+            self.contract.total_supply(id)
+        })
+        .await?;
+
+        let contract_address = checksum_address(&self.contract.address());
+
+        Ok(NamiLandNFTCollection {
+            network,
+            contract_address,
+            name,
+            symbol,
+            base_token_uri,
+            owner: checksum_address(&owner),
+            tokens: nft_ids
+                .iter()
+                .zip(total_supplies.iter())
+                .map(|(id, total_supply)| CollectionToken {
+                    nft_id: id.to_string(),
+                    total_supply: total_supply.to_string(),
+                })
+                .collect(),
+        })
+    }
+
+    // get collection metadata in the shape of OpenSea `contractURI`.
+    pub async fn get_contract_uri(&self) -> Result<ContractURIMetadata, Error> {
+        let name_call = self.contract.name();
+        let owner_call = self.contract.owner();
+        let (name, owner) = tokio::try_join!(name_call.call(), owner_call.call())?;
+
+        Ok(ContractURIMetadata {
+            name,
+            description: self
+                .collection
+                .description
+                .clone()
+                .unwrap_or_else(|| NAMILAND_GAME_ITEM_COLLECTION_DESCRIPTION.to_string()),
+            image: None,
+            external_link: None,
+            seller_fee_basis_points: 0,
+            fee_recipient: checksum_address(&owner),
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use ethers::types::U256;
+    use ethers::types::{Address, H256, U256};
 
-    use crate::services::namiland_erc1155::NamiLandERC1155Service;
+    use crate::services::{
+        asset_events::AssetTransfer,
+        namiland_erc1155::{HolderSnapshot, NamiLandERC1155Service},
+    };
 
     #[test]
     fn test_get_nft_metadata() {
@@ -193,4 +712,46 @@ mod tests {
         );
         println!("{:?}", metadata);
     }
+
+    #[test]
+    fn test_apply_holder_transfers_in_chain_order() {
+        let minter = Address::from_low_u64_be(1);
+        let player = Address::from_low_u64_be(2);
+        let transfer =
+            |log_index: u64, from: Address, to: Address, ids: &[u64], amounts: &[u64]| {
+                AssetTransfer {
+                    block_number: 10,
+                    tx_hash: H256::zero(),
+                    log_index,
+                    contract_address: Address::zero(),
+                    from,
+                    to,
+                    nft_ids: ids.iter().map(|id| U256::from(*id)).collect(),
+                    amounts: amounts.iter().map(|amount| U256::from(*amount)).collect(),
+                }
+            };
+
+        // a batch mint and a single transfer of the minted items in the same range, the
+        // single transfer is queried first.
+        let mut snapshot = HolderSnapshot::default();
+        snapshot.apply_range(
+            vec![
+                transfer(1, minter, player, &[10001], &[3]),
+                transfer(0, Address::zero(), minter, &[10001, 10002], &[5, 2]),
+            ],
+            20,
+        );
+        let holders = &snapshot.balances[&U256::from(10001)];
+        assert_eq!(holders[&minter], U256::from(2));
+        assert_eq!(holders[&player], U256::from(3));
+        assert_eq!(
+            snapshot.balances[&U256::from(10002)][&minter],
+            U256::from(2)
+        );
+        assert_eq!(snapshot.next_block, 21);
+
+        // a holder transferring everything away is removed.
+        snapshot.apply_range(vec![transfer(0, player, minter, &[10001], &[3])], 30);
+        assert!(!snapshot.balances[&U256::from(10001)].contains_key(&player));
+    }
 }
@@ -1,7 +1,12 @@
-use crate::common::{
-    address::get_contract_address,
-    defines::{Error, NetworkType, SupportedContractType},
-    provider::ProviderManager,
+use crate::{
+    common::{
+        address::checksum_address,
+        config::config,
+        defines::{Error, NetworkType},
+        finality::at_block,
+        provider::ProviderManager,
+    },
+    models::NamiXStakedInfo,
 };
 use ethers::{
     prelude::abigen,
@@ -15,31 +20,72 @@ abigen!(
     event_derives(serde::Deserialize, serde::Serialize)
 );
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct NecoStakeService {
     contract: NecoStakeContract<Provider<Http>>,
+    block_number: Option<u64>,
 }
 
-// impl NecoStakeService {
-//     pub fn new(network: NetworkType) -> NecoStakeService {
-//         let client = ProviderManager::instance()
-//             .get_provider(network)
-//             .expect("get provider failed");
-//         let address = get_contract_address(&SupportedContractType::NamiLandGameItemNFT, &network)
-//             .expect("get contract address failed");
-//         let contract = NecoStakeContract::new(address, client.clone());
-//         NecoStakeService { contract }
-//     }
-// }
-//
-// impl NecoStakeService {
-//     pub async fn get_neco_staked_amount(&self, account: &str) -> Result<U256, Error> {
-//         let address = account.parse::<Address>()?;
-//         Ok(self.contract.get_staked_neco_amount(address).call().await?)
-//     }
-//
-//     pub async fn get_neco_staked_time(&self, account: &str) -> Result<U256, Error> {
-//         let address = account.parse::<Address>()?;
-//         Ok(self.contract.get_staked_time_period(address).call().await?)
-//     }
-// }
+impl NecoStakeService {
+    pub fn new(network: NetworkType) -> Result<NecoStakeService, Error> {
+        let client = match ProviderManager::instance().get_provider(network) {
+            Some(client) => client,
+            None => return Err("get provider failed".into()),
+        };
+        let address = config()
+            .stake_contract(network)
+            .ok_or("stake contract is not configured on the network")?;
+        let contract = NecoStakeContract::new(address, client.clone());
+        Ok(NecoStakeService {
+            contract,
+            block_number: None,
+        })
+    }
+
+    // read staked info at the block resolved from the `finality` param.
+    pub fn at_block(mut self, block_number: Option<u64>) -> NecoStakeService {
+        self.block_number = block_number;
+        self
+    }
+}
+
+impl NecoStakeService {
+    pub async fn get_neco_staked_amount(&self, account: &str) -> Result<U256, Error> {
+        let address = account.parse::<Address>()?;
+        let amount_call = at_block(
+            self.contract.get_staked_neco_amount(address),
+            self.block_number,
+        );
+        Ok(amount_call.call().await?)
+    }
+
+    pub async fn get_neco_staked_time(&self, account: &str) -> Result<U256, Error> {
+        let address = account.parse::<Address>()?;
+        let time_call = at_block(
+            self.contract.get_staked_time_period(address),
+            self.block_number,
+        );
+        Ok(time_call.call().await?)
+    }
+
+    // get staked amount and time of an account together.
+    pub async fn get_staked_info(&self, account: Address) -> Result<NamiXStakedInfo, Error> {
+        let amount_call = at_block(
+            self.contract.get_staked_neco_amount(account),
+            self.block_number,
+        );
+        let time_call = at_block(
+            self.contract.get_staked_time_period(account),
+            self.block_number,
+        );
+        let (staked_amount, staked_time) = tokio::try_join!(amount_call.call(), time_call.call())?;
+        Ok(NamiXStakedInfo {
+            public_address: checksum_address(&account),
+            primary_name: None,
+            is_contract: None,
+            staked_amount: staked_amount.to_string(),
+            staked_time: staked_time.to_string(),
+            block_number: self.block_number,
+        })
+    }
+}
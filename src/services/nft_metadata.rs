@@ -0,0 +1,306 @@
+use std::{future::Future, time::Duration};
+
+use ethers::{
+    prelude::Lazy,
+    types::{Address, U256},
+};
+use serde_json::{Map, Value};
+use tokio::sync::Mutex;
+
+use crate::{
+    common::{
+        cache::{get_or_load, get_or_load_data, TtlCache},
+        defines::{
+            Error, NetworkType, NFT_METADATA_CACHE_MAX_SIZE, NFT_METADATA_CACHE_TTL_SECS,
+            NFT_METADATA_NEGATIVE_CACHE_TTL_SECS,
+        },
+        http_cache::invalidate_responses,
+        uri::{fetch_uri_content, substitute_id},
+    },
+    models::{NFTTrait, NamiLandERC1155NFTMetadata},
+};
+
+// network, contract address and token id, shared by ERC1155 and ERC721 collections.
+pub type MetadataKey = (NetworkType, Address, U256);
+
+static NFT_URL_CACHES: Lazy<Mutex<TtlCache<MetadataKey, String>>> =
+    Lazy::new(|| Mutex::new(new_metadata_cache()));
+static NFT_METADATA_CACHES: Lazy<Mutex<TtlCache<MetadataKey, NamiLandERC1155NFTMetadata>>> =
+    Lazy::new(|| Mutex::new(new_metadata_cache()));
+
+fn new_metadata_cache<V: Clone>() -> TtlCache<MetadataKey, V> {
+    TtlCache::new(
+        Duration::from_secs(NFT_METADATA_CACHE_TTL_SECS),
+        Duration::from_secs(NFT_METADATA_NEGATIVE_CACHE_TTL_SECS),
+        NFT_METADATA_CACHE_MAX_SIZE,
+    )
+}
+
+// get the cached token uri, or load it from the contract with `load_uri`.
+pub async fn get_cached_uri<F>(key: MetadataKey, load_uri: F) -> Result<String, Error>
+where
+    F: Future<Output = Result<String, Error>>,
+{
+    get_or_load(&NFT_URL_CACHES, key, load_uri).await
+}
+
+// get the cached metadata, or resolve the token uri and fetch the metadata from it.
+pub async fn get_cached_metadata<F>(
+    key: MetadataKey,
+    load_uri: F,
+) -> Result<NamiLandERC1155NFTMetadata, Error>
+where
+    F: Future<Output = Result<String, Error>>,
+{
+    get_or_load_data(&NFT_METADATA_CACHES, key, async {
+        let uri = get_cached_uri(key, load_uri).await?;
+        fetch_metadata(&uri, &key.2).await
+    })
+    .await
+}
+
+// fetch metadata from a fresh token uri. The cache is only updated on success,
+// so a failed refresh keeps serving the cached metadata.
+pub async fn refresh_cached_metadata(key: MetadataKey, uri: String) -> Result<(), Error> {
+    let metadata = fetch_metadata(&uri, &key.2).await?;
+    NFT_URL_CACHES.lock().await.insert(key, uri);
+    NFT_METADATA_CACHES.lock().await.insert(key, metadata);
+    Ok(())
+}
+
+// drop cached uri and metadata of one token, or of all tokens of a contract on the network.
+pub async fn invalidate_cached_metadata(
+    network: NetworkType,
+    contract_address: Address,
+    token_id: Option<U256>,
+) -> usize {
+    let matches = |key: &MetadataKey| {
+        key.0 == network
+            && key.1 == contract_address
+            && (token_id.is_none() || token_id == Some(key.2))
+    };
+    NFT_URL_CACHES.lock().await.remove_where(matches);
+    let purged = NFT_METADATA_CACHES.lock().await.remove_where(matches);
+
+    // cached responses built from the metadata are dropped as well.
+    let chain_id = network.chain_id();
+    let token_path = match token_id {
+        Some(token_id) => format!("{}?", token_id),
+        None => String::new(),
+    };
+    for path_prefix in [
+        format!(
+            "/v1/namiland-game-item-nft/metadata/{}/{}",
+            chain_id, token_path
+        ),
+        format!(
+            "/v1/erc721/metadata/{}/{:?}/{}",
+            chain_id, contract_address, token_path
+        ),
+        format!("/v1/namiland-game-item-nft/contract-uri/{}?", chain_id),
+        "/v1/namiland-game-item-nft/catalogue".to_string(),
+    ] {
+        invalidate_responses(&path_prefix).await;
+    }
+    purged
+}
+
+// get metadata from metadata server, gateway or the uri itself.
+async fn fetch_metadata(uri: &str, token_id: &U256) -> Result<NamiLandERC1155NFTMetadata, Error> {
+    let content = fetch_uri_content(&substitute_id(uri, token_id)).await?;
+    let mut metadata = parse_metadata(&content)?;
+    if metadata.id.is_empty() {
+        metadata.id = token_id.to_string();
+    }
+    Ok(metadata)
+}
+
+// Parse nft metadata leniently: missing fields are defaulted, numbers and booleans
+// are accepted where strings are expected, unknown fields are kept in `extra`, and
+// the problems are reported in `warnings` instead of failing the whole request.
+pub fn parse_metadata(content: &str) -> Result<NamiLandERC1155NFTMetadata, Error> {
+    let mut object = match serde_json::from_str(content)? {
+        Value::Object(object) => object,
+        _ => return Err("metadata is not a json object".into()),
+    };
+    let mut warnings = vec![];
+
+    let mut metadata = NamiLandERC1155NFTMetadata {
+        id: take_string(&mut object, &["id"], false, &mut warnings),
+        name: take_string(&mut object, &["name"], true, &mut warnings),
+        description: take_string(&mut object, &["description"], false, &mut warnings),
+        nft_type1: take_string(
+            &mut object,
+            &["nft_type1", "nftType1"],
+            false,
+            &mut warnings,
+        ),
+        nft_type2: take_string(
+            &mut object,
+            &["nft_type2", "nftType2"],
+            false,
+            &mut warnings,
+        ),
+        image: take_string(&mut object, &["image", "image_url"], true, &mut warnings),
+        external_url: take_string(
+            &mut object,
+            &["external_url", "externalUrl"],
+            false,
+            &mut warnings,
+        ),
+        animation_url: take_optional_string(
+            &mut object,
+            &["animation_url", "animationUrl"],
+            &mut warnings,
+        ),
+        background_color: take_optional_string(
+            &mut object,
+            &["background_color", "backgroundColor"],
+            &mut warnings,
+        ),
+        properties: object.remove("properties"),
+        ..Default::default()
+    };
+    metadata.attributes = match object.remove("attributes") {
+        Some(Value::Array(attributes)) => attributes
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, attribute)| parse_trait(index, attribute, &mut warnings))
+            .collect(),
+        Some(Value::Null) | None => vec![],
+        Some(_) => {
+            warnings.push("attributes is not an array".to_string());
+            vec![]
+        }
+    };
+    metadata.extra = object.into_iter().collect();
+    metadata.warnings = warnings;
+
+    Ok(metadata)
+}
+
+fn parse_trait(index: usize, attribute: Value, warnings: &mut Vec<String>) -> Option<NFTTrait> {
+    let mut object = match attribute {
+        Value::Object(object) => object,
+        _ => {
+            warnings.push(format!("attributes[{}] is not an object, skipped", index));
+            return None;
+        }
+    };
+    let value = match object.remove("value") {
+        Some(value @ (Value::String(_) | Value::Number(_) | Value::Bool(_))) => value,
+        _ => {
+            warnings.push(format!(
+                "attributes[{}].value is missing or invalid, skipped",
+                index
+            ));
+            return None;
+        }
+    };
+    let mut trait_warnings = vec![];
+    let nft_trait = NFTTrait {
+        trait_type: take_string(
+            &mut object,
+            &["trait_type", "traitType"],
+            true,
+            &mut trait_warnings,
+        ),
+        value,
+        display_type: take_optional_string(
+            &mut object,
+            &["display_type", "displayType"],
+            &mut trait_warnings,
+        ),
+    };
+    warnings.extend(
+        trait_warnings
+            .into_iter()
+            .map(|warning| format!("attributes[{}].{}", index, warning)),
+    );
+    Some(nft_trait)
+}
+
+// take a string field by any of its names, numbers and booleans are converted to string.
+fn take_string(
+    object: &mut Map<String, Value>,
+    keys: &[&str],
+    required: bool,
+    warnings: &mut Vec<String>,
+) -> String {
+    match take_optional_string(object, keys, warnings) {
+        Some(value) => value,
+        None => {
+            if required {
+                warnings.push(format!("{} is missing", keys[0]));
+            }
+            String::new()
+        }
+    }
+}
+
+fn take_optional_string(
+    object: &mut Map<String, Value>,
+    keys: &[&str],
+    warnings: &mut Vec<String>,
+) -> Option<String> {
+    let value = keys.iter().find_map(|key| object.remove(*key))?;
+    match value {
+        Value::String(value) => Some(value),
+        Value::Number(value) => Some(value.to_string()),
+        Value::Bool(value) => Some(value.to_string()),
+        Value::Null => None,
+        _ => {
+            warnings.push(format!("{} is not a string, ignored", keys[0]));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::services::nft_metadata::parse_metadata;
+
+    #[test]
+    fn test_parse_lenient_metadata() {
+        let content = json!({
+            "id": 10001,
+            "name": "Bamboo Rod",
+            "image": "ipfs://QmHash/10001.png",
+            "animation_url": "ipfs://QmHash/10001.mp4",
+            "attributes": [
+                {"trait_type": "Type", "value": "Rod"},
+                {"trait_type": "Level", "value": 3, "display_type": "number"},
+                {"trait_type": "Tradable", "value": true},
+                {"trait_type": "Broken"},
+                "invalid"
+            ],
+            "rarity": "common"
+        })
+        .to_string();
+
+        let metadata = parse_metadata(&content).unwrap();
+        assert_eq!(metadata.id, "10001");
+        assert_eq!(metadata.name, "Bamboo Rod");
+        assert_eq!(metadata.nft_type1, "");
+        assert_eq!(
+            metadata.animation_url.as_deref(),
+            Some("ipfs://QmHash/10001.mp4")
+        );
+        assert_eq!(metadata.attributes.len(), 3);
+        assert_eq!(metadata.attributes[1].value, json!(3));
+        assert_eq!(
+            metadata.attributes[1].display_type.as_deref(),
+            Some("number")
+        );
+        assert_eq!(metadata.extra.get("rarity"), Some(&json!("common")));
+        assert_eq!(metadata.warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_invalid_metadata() {
+        assert!(parse_metadata("[]").is_err());
+        assert!(parse_metadata("not json").is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use ethers::{
+    prelude::{abigen, Lazy},
+    types::{Address, U256},
+    utils::format_units,
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    common::{
+        address::checksum_address,
+        cache::{get_or_load, get_or_load_data, TtlCache},
+        config::{config, PricePairConfig},
+        defines::{
+            Error, NetworkType, PRICE_CACHE_MAX_SIZE, PRICE_CACHE_TTL_SECS,
+            PRICE_NEGATIVE_CACHE_TTL_SECS,
+        },
+        provider::ProviderManager,
+    },
+    models::{ERC20BalanceBatch, ERC20Token, TokenPrice, TokenPrices},
+    services::erc20::ERC20Contract,
+};
+
+abigen!(PancakePairContract, "./src/abi/pancake_pair.json");
+
+// usd prices by network and pair address.
+static PRICE_CACHES: Lazy<Mutex<TtlCache<(NetworkType, Address), f64>>> = Lazy::new(|| {
+    Mutex::new(TtlCache::new(
+        Duration::from_secs(PRICE_CACHE_TTL_SECS),
+        Duration::from_secs(PRICE_NEGATIVE_CACHE_TTL_SECS),
+        PRICE_CACHE_MAX_SIZE,
+    ))
+});
+
+// get the usd price of a token from its configured pair on the network.
+pub async fn get_usd_price(network: NetworkType, token: Address) -> Result<f64, Error> {
+    let pair = match config().find_price_pair(network, token) {
+        Some(pair) => pair,
+        None => {
+            return Err(format!(
+                "no price pair for {} on {}",
+                checksum_address(&token),
+                network
+            )
+            .into())
+        }
+    };
+    get_pair_price(pair).await
+}
+
+// usd prices of all configured pairs.
+pub async fn get_prices() -> TokenPrices {
+    let mut prices = vec![];
+    for pair in &config().price_pairs {
+        // prices are the data here, cache hits are reported in the response meta.
+        let price = get_or_load_data(
+            &PRICE_CACHES,
+            (pair.network, pair.pair),
+            load_pair_price(pair),
+        )
+        .await
+        .map_err(|err| price_error(pair, err));
+        prices.push(TokenPrice {
+            symbol: pair.symbol.clone(),
+            network: pair.network,
+            pair_address: checksum_address(&pair.pair),
+            usd_price: price.as_ref().ok().copied(),
+            error: price.err().map(|err| err.to_string()),
+        });
+    }
+    TokenPrices { prices }
+}
+
+// set usd price and value of a token balance, left empty when the token has no price.
+pub async fn value_token(network: NetworkType, token_address: Address, token: &mut ERC20Token) {
+    if let Ok(price) = get_usd_price(network, token_address).await {
+        token.usd_price = Some(price);
+        token.usd_value = to_usd_value(&token.amount, token.decimal, price);
+    }
+}
+
+// set usd price and values of balances of many addresses.
+pub async fn value_balance_batch(
+    network: NetworkType,
+    token_address: Address,
+    batch: &mut ERC20BalanceBatch,
+) {
+    let price = match get_usd_price(network, token_address).await {
+        Ok(price) => price,
+        Err(_) => return,
+    };
+    batch.usd_price = Some(price);
+    batch.total_usd_value = to_usd_value(&batch.total_amount, batch.decimal, price);
+    for balance in batch.balances.iter_mut() {
+        balance.usd_value = to_usd_value(&balance.amount, batch.decimal, price);
+    }
+}
+
+// a cached price valuing a fresh balance does not make the balance cached.
+async fn get_pair_price(pair: &PricePairConfig) -> Result<f64, Error> {
+    get_or_load(
+        &PRICE_CACHES,
+        (pair.network, pair.pair),
+        load_pair_price(pair),
+    )
+    .await
+    .map_err(|err| price_error(pair, err))
+}
+
+fn price_error(pair: &PricePairConfig, err: Error) -> Error {
+    format!("price of {}: {}", pair.symbol, err).into()
+}
+
+// price the token by the reserves of the pair, adjusted by decimals of both tokens.
+async fn load_pair_price(pair: &PricePairConfig) -> Result<f64, Error> {
+    let client = match ProviderManager::instance().get_provider(pair.network) {
+        Some(client) => client,
+        None => return Err("get provider failed".into()),
+    };
+    let contract = PancakePairContract::new(pair.pair, client.clone());
+    let reserves_call = contract.get_reserves();
+    let token0_call = contract.token_0();
+    let token1_call = contract.token_1();
+    let ((reserve0, reserve1, _), token0, token1) =
+        tokio::try_join!(reserves_call.call(), token0_call.call(), token1_call.call())?;
+    let (token_reserve, quote, quote_reserve) = if token0 == pair.token {
+        (reserve0, token1, reserve1)
+    } else if token1 == pair.token {
+        (reserve1, token0, reserve0)
+    } else {
+        return Err("token is not in the pair".into());
+    };
+
+    let token_decimals_call = ERC20Contract::new(pair.token, client.clone()).decimals();
+    let quote_decimals_call = ERC20Contract::new(quote, client).decimals();
+    let (token_decimals, quote_decimals) =
+        tokio::try_join!(token_decimals_call.call(), quote_decimals_call.call())?;
+    price_from_reserves(
+        U256::from(token_reserve),
+        token_decimals,
+        U256::from(quote_reserve),
+        quote_decimals,
+    )
+    .ok_or_else(|| "pair has no liquidity".into())
+}
+
+pub fn price_from_reserves(
+    token_reserve: U256,
+    token_decimals: u8,
+    quote_reserve: U256,
+    quote_decimals: u8,
+) -> Option<f64> {
+    let token_amount = to_decimal(token_reserve, token_decimals)?;
+    let quote_amount = to_decimal(quote_reserve, quote_decimals)?;
+    if token_amount <= 0.0 {
+        return None;
+    }
+    Some(quote_amount / token_amount)
+}
+
+// usd value of a raw token amount.
+pub fn to_usd_value(amount: &str, decimals: u8, price: f64) -> Option<f64> {
+    let amount = U256::from_dec_str(amount).ok()?;
+    Some(to_decimal(amount, decimals)? * price)
+}
+
+fn to_decimal(amount: U256, decimals: u8) -> Option<f64> {
+    format_units(amount, decimals as u32).ok()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::U256;
+
+    use crate::services::price::{price_from_reserves, to_usd_value};
+
+    #[test]
+    fn test_price_from_reserves() {
+        // 1000 tokens of 18 decimals against 250 usd of 6 decimals.
+        let price =
+            price_from_reserves(U256::exp10(21), 18, U256::from(250_000_000u64), 6).unwrap();
+        assert!((price - 0.25).abs() < 1e-12);
+        assert!(price_from_reserves(U256::zero(), 18, U256::from(1), 18).is_none());
+
+        let value = to_usd_value("1500000000000000000", 18, price).unwrap();
+        assert!((value - 0.375).abs() < 1e-12);
+        assert!(to_usd_value("abc", 18, price).is_none());
+    }
+}
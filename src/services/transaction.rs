@@ -0,0 +1,266 @@
+use ethers::{
+    abi::{self, Abi, ParamType, RawLog, Token},
+    providers::Middleware,
+    types::{transaction::eip2718::TypedTransaction, Log, TxHash, U256},
+    utils::hex,
+};
+use serde_json::Value;
+
+use crate::{
+    common::{
+        address::checksum_address,
+        defines::{Error, NetworkType},
+        provider::ProviderManager,
+    },
+    models::{TransactionEvent, TransactionEventParam, TransactionStatus, TransactionStatusType},
+    services::{
+        erc20::ERC20CONTRACT_ABI, erc721::ERC721CONTRACT_ABI,
+        namiland_erc1155::NAMILANDERC1155CONTRACT_ABI, neco_stake::NECOSTAKECONTRACT_ABI,
+    },
+};
+
+// selectors of solidity `Error(string)` and `Panic(uint256)` revert data.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+// get the status of a transaction, with its receipt logs decoded and the revert reason
+// of a failed transaction.
+pub async fn get_transaction_status(
+    network: NetworkType,
+    tx_hash: TxHash,
+) -> Result<TransactionStatus, Error> {
+    let client = match ProviderManager::instance().get_provider(network) {
+        Some(client) => client,
+        None => return Err("get provider failed".into()),
+    };
+    let mut status = TransactionStatus {
+        tx_hash: format!("{:?}", tx_hash),
+        network,
+        ..Default::default()
+    };
+    let tx = match client.get_transaction(tx_hash).await? {
+        Some(tx) => tx,
+        None => return Ok(status),
+    };
+    status.status = TransactionStatusType::Pending;
+    status.from = Some(checksum_address(&tx.from));
+    status.to = tx.to.as_ref().map(checksum_address);
+    let (receipt, latest_block) = tokio::try_join!(
+        client.get_transaction_receipt(tx_hash),
+        client.get_block_number()
+    )?;
+    let (receipt, block_number) = match receipt {
+        Some(receipt) => match receipt.block_number {
+            Some(block_number) => (receipt, block_number.as_u64()),
+            None => return Ok(status),
+        },
+        None => return Ok(status),
+    };
+
+    status.block_number = Some(block_number);
+    status.confirmations = Some(
+        latest_block
+            .as_u64()
+            .saturating_sub(block_number)
+            .saturating_add(1),
+    );
+    status.gas_used = receipt.gas_used.map(|gas| gas.to_string());
+    status.effective_gas_price = receipt.effective_gas_price.map(|price| price.to_string());
+    status.events = receipt.logs.iter().filter_map(decode_log).collect();
+    if receipt.status.map(|status| status.as_u64()) == Some(1) {
+        status.status = TransactionStatusType::Confirmed;
+        return Ok(status);
+    }
+
+    status.status = TransactionStatusType::Failed;
+    // replay the transaction on the state before its block, the node reports the revert.
+    // Nodes without the state of old blocks can not replay it, the reason is left empty.
+    let call: TypedTransaction = (&tx).into();
+    let parent_block = block_number.saturating_sub(1);
+    if let Err(err) = client.call(&call, Some(parent_block.into())).await {
+        status.revert_reason = parse_revert_reason(&err.to_string());
+    }
+    Ok(status)
+}
+
+// decode a log with the first known abi having an event of its signature.
+fn decode_log(log: &Log) -> Option<TransactionEvent> {
+    let topic = *log.topics.first()?;
+    let abis: [(&str, &Abi); 4] = [
+        ("erc20", &ERC20CONTRACT_ABI),
+        ("erc721", &ERC721CONTRACT_ABI),
+        ("namiland_erc1155", &NAMILANDERC1155CONTRACT_ABI),
+        ("neco_stake", &NECOSTAKECONTRACT_ABI),
+    ];
+    abis.iter().find_map(|(contract_type, abi)| {
+        // erc20 and erc721 transfers share the signature, they differ in indexed params.
+        let event = abi.events().find(|event| event.signature() == topic)?;
+        let decoded = event
+            .parse_log(RawLog {
+                topics: log.topics.clone(),
+                data: log.data.to_vec(),
+            })
+            .ok()?;
+        Some(TransactionEvent {
+            log_index: log.log_index.map(|index| index.as_u64()),
+            contract_address: checksum_address(&log.address),
+            contract_type: contract_type.to_string(),
+            event: event.name.clone(),
+            params: decoded
+                .params
+                .into_iter()
+                .map(|param| TransactionEventParam {
+                    name: param.name,
+                    value: token_to_value(param.value),
+                })
+                .collect(),
+        })
+    })
+}
+
+fn token_to_value(token: Token) -> Value {
+    match token {
+        Token::Address(address) => Value::String(checksum_address(&address)),
+        Token::Uint(value) => Value::String(value.to_string()),
+        Token::Int(value) => Value::String(int_to_string(value)),
+        Token::Bool(value) => Value::Bool(value),
+        Token::String(value) => Value::String(value),
+        Token::Bytes(value) | Token::FixedBytes(value) => {
+            Value::String(format!("0x{}", hex::encode(value)))
+        }
+        Token::Array(tokens) | Token::FixedArray(tokens) | Token::Tuple(tokens) => {
+            Value::Array(tokens.into_iter().map(token_to_value).collect())
+        }
+    }
+}
+
+// format a two's complement int256 as a signed decimal string.
+fn int_to_string(value: U256) -> String {
+    if value.bit(255) {
+        format!("-{}", (!value).overflowing_add(U256::one()).0)
+    } else {
+        value.to_string()
+    }
+}
+
+// Get the revert reason from the error of a reverted call. The revert data is decoded
+// when the node returns it, otherwise the reason in the message is used.
+pub fn parse_revert_reason(message: &str) -> Option<String> {
+    if let Some(reason) = find_revert_data(message).and_then(|data| decode_revert_data(&data)) {
+        return Some(reason);
+    }
+    let (_, reason) = message.split_once("execution reverted: ")?;
+    let reason = reason.split([',', ')', '"']).next()?.trim();
+    Some(reason.to_string()).filter(|reason| !reason.is_empty())
+}
+
+// find the hex revert data of an `Error(string)` or `Panic(uint256)` in the message.
+fn find_revert_data(message: &str) -> Option<Vec<u8>> {
+    [ERROR_SELECTOR, PANIC_SELECTOR]
+        .iter()
+        .find_map(|selector| {
+            let start = message.find(&format!("0x{}", hex::encode(selector)))?;
+            let data: String = message[start + 2..]
+                .chars()
+                .take_while(|c| c.is_ascii_hexdigit())
+                .collect();
+            hex::decode(data).ok()
+        })
+}
+
+fn decode_revert_data(data: &[u8]) -> Option<String> {
+    if data.len() < 4 {
+        return None;
+    }
+    let (selector, args) = data.split_at(4);
+    if selector == ERROR_SELECTOR {
+        match abi::decode(&[ParamType::String], args).ok()?.pop()? {
+            Token::String(reason) => Some(reason),
+            _ => None,
+        }
+    } else if selector == PANIC_SELECTOR {
+        match abi::decode(&[ParamType::Uint(256)], args).ok()?.pop()? {
+            Token::Uint(code) => Some(format!("panic: 0x{:02x}", code)),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::{
+        abi::{self, Token},
+        types::{Address, Bytes, Log, H256, U256},
+    };
+
+    use crate::services::{
+        erc20::ERC20CONTRACT_ABI,
+        transaction::{decode_log, parse_revert_reason},
+    };
+
+    #[test]
+    fn test_parse_revert_reason() {
+        let data = format!(
+            "0x08c379a0{}",
+            ethers::utils::hex::encode(abi::encode(&[Token::String(
+                "Stake: amount is zero".to_string()
+            )]))
+        );
+        let message = format!(
+            "(code: 3, message: execution reverted: Stake: amount is zero, data: Some(String(\"{}\")))",
+            data
+        );
+        assert_eq!(
+            parse_revert_reason(&message).as_deref(),
+            Some("Stake: amount is zero")
+        );
+        assert_eq!(
+            parse_revert_reason(
+                "(code: -32000, message: execution reverted: not owner, data: None)"
+            )
+            .as_deref(),
+            Some("not owner")
+        );
+        let panic = format!(
+            "execution reverted, data: 0x4e487b71{}",
+            ethers::utils::hex::encode(abi::encode(&[Token::Uint(U256::from(0x11))]))
+        );
+        assert_eq!(parse_revert_reason(&panic).as_deref(), Some("panic: 0x11"));
+        assert_eq!(parse_revert_reason("execution reverted"), None);
+    }
+
+    #[test]
+    fn test_decode_transfer_log() {
+        let transfer = ERC20CONTRACT_ABI.event("Transfer").unwrap().signature();
+        let from = Address::from_low_u64_be(1);
+        let to = Address::from_low_u64_be(2);
+        let log = Log {
+            address: Address::from_low_u64_be(3),
+            topics: vec![transfer, H256::from(from), H256::from(to)],
+            data: Bytes::from(abi::encode(&[Token::Uint(U256::exp10(18))])),
+            ..Default::default()
+        };
+        let event = decode_log(&log).unwrap();
+        assert_eq!(event.contract_type, "erc20");
+        assert_eq!(event.event, "Transfer");
+        assert_eq!(event.params.len(), 3);
+        assert_eq!(event.params[2].value, "1000000000000000000");
+
+        // an erc721 transfer has the token id indexed.
+        let log = Log {
+            topics: vec![
+                transfer,
+                H256::from(from),
+                H256::from(to),
+                H256::from_low_u64_be(7),
+            ],
+            data: Bytes::default(),
+            ..log
+        };
+        let event = decode_log(&log).unwrap();
+        assert_eq!(event.contract_type, "erc721");
+        assert_eq!(event.params[2].value, "7");
+    }
+}
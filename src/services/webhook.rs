@@ -0,0 +1,537 @@
+use std::{
+    env,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use ethers::{
+    prelude::Lazy,
+    providers::Middleware,
+    types::{Address, H256},
+    utils::hex,
+};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::sync::Mutex;
+
+use crate::{
+    common::{
+        address::checksum_address,
+        config::config,
+        defines::{
+            Error, NetworkType, DEFAULT_WEBHOOK_STORE_PATH, WEBHOOK_DELIVERY_LOG_SIZE,
+            WEBHOOK_MAX_ATTEMPTS, WEBHOOK_REQUEST_TIMEOUT_SECS, WEBHOOK_RETRY_BASE_SECS,
+            WEBHOOK_RETRY_MAX_SECS,
+        },
+        provider::ProviderManager,
+    },
+    models::{
+        WebhookDelivery, WebhookDeliveryStatus, WebhookEventType, WebhookPayload,
+        WebhookSubscription,
+    },
+    services::asset_events::AssetTransfer,
+};
+
+// a subscription with the secret its payloads are signed with, never served by the apis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredSubscription {
+    #[serde(flatten)]
+    pub subscription: WebhookSubscription,
+    pub secret: String,
+}
+
+// subscriptions and deliveries, persisted as json after every change.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct WebhookStore {
+    subscriptions: Vec<StoredSubscription>,
+    deliveries: Vec<WebhookDelivery>,
+}
+
+static WEBHOOK_STORE: Lazy<Mutex<Option<WebhookStore>>> = Lazy::new(|| Mutex::new(None));
+
+fn store_path() -> String {
+    env::var("WEBHOOK_STORE_PATH").unwrap_or_else(|_| DEFAULT_WEBHOOK_STORE_PATH.to_string())
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+// run `update` on the store, loaded from disk on first use, and save it when `save`.
+async fn with_store<T>(save: bool, update: impl FnOnce(&mut WebhookStore) -> T) -> T {
+    let mut store = WEBHOOK_STORE.lock().await;
+    if store.is_none() {
+        let loaded = match tokio::fs::read(store_path()).await {
+            Ok(content) => serde_json::from_slice(&content).unwrap_or_else(|err| {
+                log::warn!("webhook store {} is invalid: {}", store_path(), err);
+                WebhookStore::default()
+            }),
+            Err(_) => WebhookStore::default(),
+        };
+        *store = Some(loaded);
+    }
+    let store = store.get_or_insert_with(WebhookStore::default);
+    let result = update(store);
+    if save {
+        if let Err(err) = save_store(store).await {
+            log::warn!("save webhook store {} failed: {}", store_path(), err);
+        }
+    }
+    result
+}
+
+// write to a temporary file first, so a crash never leaves a truncated store.
+async fn save_store(store: &WebhookStore) -> Result<(), Error> {
+    let path = store_path();
+    let temp_path = format!("{}.tmp", path);
+    tokio::fs::write(&temp_path, serde_json::to_vec_pretty(store)?).await?;
+    tokio::fs::rename(&temp_path, &path).await?;
+    Ok(())
+}
+
+// Register a subscription, events are scanned from the block after the latest block.
+pub async fn register_subscription(
+    mut subscription: WebhookSubscription,
+    secret: String,
+) -> Result<WebhookSubscription, Error> {
+    if subscription.event_type == WebhookEventType::Stake {
+        stake_contract(subscription.network)?;
+    }
+    let client = match ProviderManager::instance().get_provider(subscription.network) {
+        Some(client) => client,
+        None => return Err("get provider failed".into()),
+    };
+    subscription.id = hex::encode(&H256::random()[..8]);
+    subscription.next_block = client.get_block_number().await?.as_u64() + 1;
+    subscription.created_at = now_secs();
+    let stored = StoredSubscription {
+        subscription: subscription.clone(),
+        secret,
+    };
+    with_store(true, |store| store.subscriptions.push(stored)).await;
+    Ok(subscription)
+}
+
+pub async fn get_subscriptions() -> Vec<StoredSubscription> {
+    with_store(false, |store| store.subscriptions.clone()).await
+}
+
+// delete a subscription together with its deliveries.
+pub async fn delete_subscription(id: &str) -> Option<WebhookSubscription> {
+    with_store(true, |store| {
+        let index = store
+            .subscriptions
+            .iter()
+            .position(|stored| stored.subscription.id == id)?;
+        store
+            .deliveries
+            .retain(|delivery| delivery.subscription_id != id);
+        Some(store.subscriptions.remove(index).subscription)
+    })
+    .await
+}
+
+// get deliveries of a subscription newest first, none when the subscription is not found.
+pub async fn get_deliveries(
+    id: &str,
+    status: Option<WebhookDeliveryStatus>,
+    limit: usize,
+) -> Option<Vec<WebhookDelivery>> {
+    with_store(false, |store| {
+        if !store
+            .subscriptions
+            .iter()
+            .any(|stored| stored.subscription.id == id)
+        {
+            return None;
+        }
+        Some(
+            store
+                .deliveries
+                .iter()
+                .rev()
+                .filter(|delivery| delivery.subscription_id == id)
+                .filter(|delivery| status.is_none() || status == Some(delivery.status))
+                .take(limit)
+                .cloned()
+                .collect(),
+        )
+    })
+    .await
+}
+
+// Queue payloads of scanned blocks and move the cursor of the subscription to `next_block`.
+// Payloads queued before, by a scan interrupted before saving, are not queued again.
+pub async fn enqueue_deliveries(id: &str, next_block: u64, payloads: Vec<WebhookPayload>) {
+    let now = now_secs();
+    with_store(true, |store| {
+        let stored = match store
+            .subscriptions
+            .iter_mut()
+            .find(|stored| stored.subscription.id == id)
+        {
+            Some(stored) => stored,
+            // deleted during the scan.
+            None => return,
+        };
+        stored.subscription.next_block = next_block;
+        for payload in payloads {
+            if store
+                .deliveries
+                .iter()
+                .any(|delivery| delivery.id == payload.delivery_id)
+            {
+                continue;
+            }
+            store.deliveries.push(WebhookDelivery {
+                id: payload.delivery_id.clone(),
+                subscription_id: id.to_string(),
+                next_attempt_at: now,
+                created_at: now,
+                payload,
+                ..Default::default()
+            });
+        }
+    })
+    .await
+}
+
+// pending deliveries due at `now`, with the url and secret of their subscription.
+pub async fn get_due_deliveries(now: u64) -> Vec<(WebhookDelivery, String, String)> {
+    with_store(false, |store| {
+        store
+            .deliveries
+            .iter()
+            .filter(|delivery| {
+                delivery.status == WebhookDeliveryStatus::Pending && delivery.next_attempt_at <= now
+            })
+            .filter_map(|delivery| {
+                let stored = store
+                    .subscriptions
+                    .iter()
+                    .find(|stored| stored.subscription.id == delivery.subscription_id)?;
+                Some((
+                    delivery.clone(),
+                    stored.subscription.url.clone(),
+                    stored.secret.clone(),
+                ))
+            })
+            .collect()
+    })
+    .await
+}
+
+// Record an attempt, a failed delivery is retried with backoff until it runs out of attempts.
+// Logs of finished deliveries are trimmed to the latest ones of each subscription.
+pub async fn record_attempt(id: &str, result: Result<u16, (Option<u16>, String)>) {
+    let now = now_secs();
+    with_store(true, |store| {
+        let delivery = match store
+            .deliveries
+            .iter_mut()
+            .find(|delivery| delivery.id == id)
+        {
+            Some(delivery) => delivery,
+            None => return,
+        };
+        delivery.attempts += 1;
+        match result {
+            Ok(status_code) => {
+                delivery.status = WebhookDeliveryStatus::Delivered;
+                delivery.last_status_code = Some(status_code);
+                delivery.last_error = None;
+                delivery.delivered_at = Some(now);
+            }
+            Err((status_code, err)) => {
+                delivery.last_status_code = status_code;
+                delivery.last_error = Some(err);
+                if delivery.attempts >= WEBHOOK_MAX_ATTEMPTS {
+                    delivery.status = WebhookDeliveryStatus::Failed;
+                } else {
+                    delivery.next_attempt_at = now + retry_delay(delivery.attempts).as_secs();
+                }
+            }
+        }
+        let subscription_id = delivery.subscription_id.clone();
+        trim_delivery_logs(&mut store.deliveries, &subscription_id);
+    })
+    .await
+}
+
+fn trim_delivery_logs(deliveries: &mut Vec<WebhookDelivery>, subscription_id: &str) {
+    let finished = deliveries
+        .iter()
+        .filter(|delivery| {
+            delivery.subscription_id == subscription_id
+                && delivery.status != WebhookDeliveryStatus::Pending
+        })
+        .count();
+    let mut excess = finished.saturating_sub(WEBHOOK_DELIVERY_LOG_SIZE);
+    deliveries.retain(|delivery| {
+        if excess > 0
+            && delivery.subscription_id == subscription_id
+            && delivery.status != WebhookDeliveryStatus::Pending
+        {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+// delay before the attempt after `attempts` failed attempts.
+pub fn retry_delay(attempts: u32) -> Duration {
+    let delay = WEBHOOK_RETRY_BASE_SECS.saturating_mul(1 << attempts.saturating_sub(1).min(20));
+    Duration::from_secs(delay.min(WEBHOOK_RETRY_MAX_SECS))
+}
+
+// the stake contract of `[[stake_contracts]]` in config.
+pub fn stake_contract(network: NetworkType) -> Result<Address, Error> {
+    config()
+        .stake_contract(network)
+        .ok_or_else(|| "stake contract is not configured on the network".into())
+}
+
+// Payloads of the transfers matching the subscription. `stake_contract` is the NECO stake
+// contract of the network for stake subscriptions.
+pub fn build_payloads(
+    subscription: &WebhookSubscription,
+    transfers: &[AssetTransfer],
+    stake_contract: Option<Address>,
+) -> Vec<WebhookPayload> {
+    let public_addresses: Vec<Address> = subscription
+        .public_addresses
+        .iter()
+        .filter_map(|address| address.parse().ok())
+        .collect();
+    transfers
+        .iter()
+        .filter_map(|transfer| {
+            let action = match stake_contract {
+                Some(stake_contract) if transfer.to == stake_contract => Some("stake"),
+                Some(stake_contract) if transfer.from == stake_contract => Some("unstake"),
+                Some(_) => return None,
+                None => None,
+            };
+            if !public_addresses.is_empty()
+                && !public_addresses.contains(&transfer.from)
+                && !public_addresses.contains(&transfer.to)
+            {
+                return None;
+            }
+            let tx_hash = format!("{:?}", transfer.tx_hash);
+            Some(WebhookPayload {
+                delivery_id: format!("{}-{}-{}", subscription.id, tx_hash, transfer.log_index),
+                subscription_id: subscription.id.clone(),
+                event_type: subscription.event_type,
+                network: subscription.network,
+                contract_address: checksum_address(&transfer.contract_address),
+                block_number: transfer.block_number,
+                tx_hash,
+                log_index: transfer.log_index,
+                from: checksum_address(&transfer.from),
+                to: checksum_address(&transfer.to),
+                nft_ids: transfer.nft_ids.iter().map(|id| id.to_string()).collect(),
+                amounts: transfer.amounts.iter().map(|id| id.to_string()).collect(),
+                action: action.map(|action| action.to_string()),
+            })
+        })
+        .collect()
+}
+
+// hex HMAC-SHA256 of `{timestamp}.{body}` with the secret of the subscription.
+pub fn sign_payload(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+// Post a signed payload, succeeds on a 2xx status. The receiver verifies the
+// `x-webhook-signature` header against `x-webhook-timestamp` and the raw body.
+pub async fn send_payload(
+    url: &str,
+    secret: &str,
+    payload: &WebhookPayload,
+) -> Result<u16, (Option<u16>, String)> {
+    let body = serde_json::to_vec(payload).map_err(|err| (None, err.to_string()))?;
+    let timestamp = now_secs();
+    let response = reqwest::Client::new()
+        .post(url)
+        .timeout(Duration::from_secs(WEBHOOK_REQUEST_TIMEOUT_SECS))
+        .header("content-type", "application/json")
+        .header("x-webhook-id", &payload.delivery_id)
+        .header("x-webhook-timestamp", timestamp.to_string())
+        .header(
+            "x-webhook-signature",
+            format!("sha256={}", sign_payload(secret, timestamp, &body)),
+        )
+        .body(body)
+        .send()
+        .await
+        .map_err(|err| (None, err.to_string()))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err((
+            Some(status.as_u16()),
+            format!("receiver responded {}", status),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+    use axum::{
+        body::Bytes,
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        routing::post,
+        Router,
+    };
+    use ethers::types::{Address, H256, U256};
+    use tokio::sync::Mutex;
+
+    use crate::{
+        models::{WebhookEventType, WebhookSubscription},
+        services::{
+            asset_events::AssetTransfer,
+            webhook::{build_payloads, retry_delay, send_payload, sign_payload},
+        },
+    };
+
+    #[test]
+    fn test_build_payloads() {
+        let stake = Address::from_low_u64_be(9);
+        let player = Address::from_low_u64_be(1);
+        let subscription = WebhookSubscription {
+            id: "a1".to_string(),
+            event_type: WebhookEventType::Stake,
+            public_addresses: vec![format!("{:?}", player)],
+            ..Default::default()
+        };
+        let transfer = AssetTransfer {
+            block_number: 10,
+            tx_hash: H256::zero(),
+            log_index: 3,
+            contract_address: Address::from_low_u64_be(5),
+            from: player,
+            to: stake,
+            nft_ids: vec![],
+            amounts: vec![U256::from(100)],
+        };
+        let transfers = [
+            transfer.clone(),
+            AssetTransfer {
+                log_index: 4,
+                from: stake,
+                to: player,
+                ..transfer.clone()
+            },
+            AssetTransfer {
+                log_index: 5,
+                to: Address::from_low_u64_be(2),
+                ..transfer.clone()
+            },
+            AssetTransfer {
+                log_index: 6,
+                from: Address::from_low_u64_be(2),
+                ..transfer
+            },
+        ];
+
+        let payloads = build_payloads(&subscription, &transfers, Some(stake));
+        assert_eq!(payloads.len(), 2);
+        assert_eq!(payloads[0].action.as_deref(), Some("stake"));
+        assert_eq!(payloads[1].action.as_deref(), Some("unstake"));
+        assert_eq!(payloads[0].amounts, vec!["100"]);
+        assert!(payloads[0].delivery_id.starts_with("a1-0x"));
+
+        assert_eq!(retry_delay(1), Duration::from_secs(10));
+        assert_eq!(retry_delay(3), Duration::from_secs(40));
+        assert_eq!(retry_delay(30), Duration::from_secs(3600));
+    }
+
+    #[tokio::test]
+    async fn test_send_signed_payload() {
+        type Received = Arc<Mutex<Vec<(HeaderMap, Bytes)>>>;
+        async fn receive(
+            State(received): State<Received>,
+            headers: HeaderMap,
+            body: Bytes,
+        ) -> StatusCode {
+            let mut received = received.lock().await;
+            received.push((headers, body));
+            // fail the first delivery.
+            match received.len() {
+                1 => StatusCode::SERVICE_UNAVAILABLE,
+                _ => StatusCode::NO_CONTENT,
+            }
+        }
+
+        let received = Received::default();
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(received.clone());
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr: SocketAddr = listener.local_addr().unwrap();
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+
+        let url = format!("http://{}/hook", addr);
+        let secret = "0123456789abcdef";
+        let subscription = WebhookSubscription {
+            id: "b2".to_string(),
+            ..Default::default()
+        };
+        let transfer = AssetTransfer {
+            block_number: 1,
+            tx_hash: H256::zero(),
+            log_index: 0,
+            contract_address: Address::zero(),
+            from: Address::zero(),
+            to: Address::from_low_u64_be(1),
+            nft_ids: vec![],
+            amounts: vec![U256::one()],
+        };
+        let payload = build_payloads(&subscription, &[transfer], None).remove(0);
+
+        assert_eq!(
+            send_payload(&url, secret, &payload).await,
+            Err((
+                Some(503),
+                "receiver responded 503 Service Unavailable".to_string()
+            ))
+        );
+        assert_eq!(send_payload(&url, secret, &payload).await, Ok(204));
+
+        let received = received.lock().await;
+        let (headers, body) = &received[1];
+        let timestamp: u64 = headers["x-webhook-timestamp"]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(
+            headers["x-webhook-signature"].to_str().unwrap(),
+            format!("sha256={}", sign_payload(secret, timestamp, body))
+        );
+        assert_eq!(
+            headers["x-webhook-id"].to_str().unwrap(),
+            payload.delivery_id
+        );
+        let delivered: serde_json::Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(delivered["subscriptionId"], "b2");
+    }
+}
@@ -0,0 +1,3 @@
+pub mod metadata_refresher;
+pub mod uri_watcher;
+pub mod webhook_dispatcher;
@@ -0,0 +1,124 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use ethers::prelude::Lazy;
+use log::info;
+use tokio::sync::{Mutex, Semaphore};
+
+use crate::{
+    common::{
+        config::config,
+        defines::{
+            NetworkType, NFT_METADATA_FETCH_CONCURRENCY, NFT_METADATA_REFRESH_INTERVAL_SECS,
+        },
+    },
+    models::MetadataRefreshStatus,
+    services::namiland_erc1155::NamiLandERC1155Service,
+};
+
+static REFRESH_STATUS: Lazy<Mutex<HashMap<(NetworkType, String), MetadataRefreshStatus>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// status of the last metadata refresh of every collection.
+pub async fn get_refresh_status() -> Vec<MetadataRefreshStatus> {
+    let mut status: Vec<MetadataRefreshStatus> =
+        REFRESH_STATUS.lock().await.values().cloned().collect();
+    status.sort_by_key(|status| (status.network.to_string(), status.collection.clone()));
+    status
+}
+
+// Pre-fetch uri and metadata of all known nft ids of every collection at startup,
+// and refresh them on schedule so requests are served from a warm cache.
+pub async fn refresh_metadata() {
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(NFT_METADATA_REFRESH_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        for collection in &config().erc1155_collections {
+            // skip networks without a provider.
+            if let Ok(service) = NamiLandERC1155Service::from_collection(collection.clone()) {
+                refresh_collection(service).await;
+            }
+        }
+    }
+}
+
+async fn refresh_collection(service: NamiLandERC1155Service) {
+    let network = service.network;
+    let key = (network, service.collection.id.clone());
+    let started = Instant::now();
+    let mut status = MetadataRefreshStatus {
+        network,
+        collection: service.collection.id.clone(),
+        last_started_at: unix_timestamp(),
+        ..Default::default()
+    };
+    // keep the result of the previous refresh until this one finished.
+    if let Some(previous) = REFRESH_STATUS.lock().await.get(&key) {
+        status.last_finished_at = previous.last_finished_at;
+        status.duration_ms = previous.duration_ms;
+        status.refreshed = previous.refreshed;
+        status.failed = previous.failed;
+        status.last_error = previous.last_error.clone();
+    }
+    REFRESH_STATUS
+        .lock()
+        .await
+        .insert(key.clone(), status.clone());
+
+    let nft_ids = service.get_known_nft_ids().await;
+    let semaphore = Arc::new(Semaphore::new(NFT_METADATA_FETCH_CONCURRENCY));
+    let handles: Vec<_> = nft_ids
+        .into_iter()
+        .map(|id| {
+            let service = service.clone();
+            let semaphore = semaphore.clone();
+            tokio::spawn(async move {
+                let _permit = semaphore.acquire().await;
+                service
+                    .refresh_metadata(&id)
+                    .await
+                    .map_err(|err| format!("nft {}: {}", id, err))
+            })
+        })
+        .collect();
+
+    let (mut refreshed, mut failed, mut last_error) = (0, 0, None);
+    for handle in handles {
+        match handle.await {
+            Ok(Ok(())) => refreshed += 1,
+            Ok(Err(err)) => {
+                failed += 1;
+                last_error = Some(err);
+            }
+            Err(err) => {
+                failed += 1;
+                last_error = Some(err.to_string());
+            }
+        }
+    }
+
+    status.last_finished_at = Some(unix_timestamp());
+    status.duration_ms = Some(started.elapsed().as_millis() as u64);
+    status.refreshed = refreshed;
+    status.failed = failed;
+    status.last_error = last_error;
+    info!(
+        "refreshed metadata of {} on {}: {} succeeded, {} failed in {}ms",
+        service.collection.id,
+        network,
+        refreshed,
+        failed,
+        started.elapsed().as_millis()
+    );
+    REFRESH_STATUS.lock().await.insert(key, status);
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
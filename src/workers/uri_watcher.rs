@@ -0,0 +1,88 @@
+use std::{collections::HashMap, time::Duration};
+
+use ethers::{providers::Middleware, types::Address};
+use log::{info, warn};
+
+use crate::{
+    common::{
+        config::config,
+        defines::{Error, NetworkType, NFT_TRANSFER_LOG_BLOCK_RANGE, NFT_URI_WATCH_INTERVAL_SECS},
+    },
+    services::{
+        namiland_erc1155::NamiLandERC1155Service, nft_metadata::invalidate_cached_metadata,
+    },
+};
+
+#[derive(Debug, Default)]
+struct WatchState {
+    next_block: Option<u64>,
+    base_token_uri: Option<String>,
+}
+
+// Poll `URI` events and `baseTokenURI` of every collection, and invalidate cached
+// metadata once they change.
+pub async fn watch_uri_changes() {
+    let mut states: HashMap<(NetworkType, Address), WatchState> = HashMap::new();
+    let mut interval = tokio::time::interval(Duration::from_secs(NFT_URI_WATCH_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        for collection in &config().erc1155_collections {
+            // skip networks without a provider.
+            let service = match NamiLandERC1155Service::from_collection(collection.clone()) {
+                Ok(service) => service,
+                Err(_) => continue,
+            };
+            let state = states
+                .entry((collection.network, collection.address))
+                .or_default();
+            if let Err(err) = watch_once(&service, state).await {
+                warn!(
+                    "watch uri changes of {} on {} failed: {}",
+                    collection.id, collection.network, err
+                );
+            }
+        }
+    }
+}
+
+async fn watch_once(service: &NamiLandERC1155Service, state: &mut WatchState) -> Result<(), Error> {
+    let network = service.network;
+    let address = service.contract.address();
+    let latest_block = service.contract.client().get_block_number().await?.as_u64();
+
+    // collections without `baseTokenURI` are watched by `URI` events only.
+    if let Ok(base_token_uri) = service.contract.base_token_uri().call().await {
+        if matches!(&state.base_token_uri, Some(uri) if *uri != base_token_uri) {
+            let purged = invalidate_cached_metadata(network, address, None).await;
+            info!(
+                "base token uri of {} on {} changed to {}, purged {} cached metadata",
+                service.collection.id, network, base_token_uri, purged
+            );
+        }
+        state.base_token_uri = Some(base_token_uri);
+    }
+
+    // nothing is cached before the first poll, start watching from the latest block.
+    let mut from_block = state.next_block.unwrap_or(latest_block + 1);
+    while from_block <= latest_block {
+        let to_block = (from_block + NFT_TRANSFER_LOG_BLOCK_RANGE - 1).min(latest_block);
+        let events = service
+            .contract
+            .uri_filter()
+            .from_block(from_block)
+            .to_block(to_block)
+            .query()
+            .await?;
+        for event in events {
+            invalidate_cached_metadata(network, address, Some(event.id)).await;
+            info!(
+                "uri of nft {} of {} on {} changed to {}",
+                event.id, service.collection.id, network, event.value
+            );
+        }
+        from_block = to_block + 1;
+    }
+    state.next_block = Some(from_block);
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use std::{collections::HashMap, time::Duration};
+
+use ethers::{providers::Middleware, types::Address};
+use log::warn;
+
+use crate::{
+    common::{
+        address::parse_address,
+        defines::{Error, NetworkType, NFT_TRANSFER_LOG_BLOCK_RANGE, WEBHOOK_POLL_INTERVAL_SECS},
+        provider::ProviderManager,
+        tasks::spawn_all,
+    },
+    models::{WebhookEventType, WebhookSubscription},
+    services::{
+        asset_events::{get_erc1155_transfers, get_erc20_transfers},
+        webhook::{
+            build_payloads, enqueue_deliveries, get_due_deliveries, get_subscriptions, now_secs,
+            record_attempt, send_payload, stake_contract,
+        },
+    },
+};
+
+// Scan confirmed blocks for events of every webhook subscription and deliver the queued
+// payloads, failed deliveries are retried on later polls.
+pub async fn dispatch_webhooks() {
+    let mut interval = tokio::time::interval(Duration::from_secs(WEBHOOK_POLL_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        scan_subscriptions().await;
+        deliver_due().await;
+    }
+}
+
+async fn scan_subscriptions() {
+    let mut latest_blocks: HashMap<NetworkType, u64> = HashMap::new();
+    for stored in get_subscriptions().await {
+        let subscription = stored.subscription;
+        let network = subscription.network;
+        let latest_block = match latest_blocks.get(&network) {
+            Some(latest_block) => *latest_block,
+            None => match get_latest_block(network).await {
+                Ok(latest_block) => *latest_blocks.entry(network).or_insert(latest_block),
+                Err(err) => {
+                    warn!("get latest block of {} failed: {}", network, err);
+                    continue;
+                }
+            },
+        };
+        if let Err(err) = scan_once(&subscription, latest_block).await {
+            warn!("scan webhook {} failed: {}", subscription.id, err);
+        }
+    }
+}
+
+async fn get_latest_block(network: NetworkType) -> Result<u64, Error> {
+    let client = match ProviderManager::instance().get_provider(network) {
+        Some(client) => client,
+        None => return Err("get provider failed".into()),
+    };
+    Ok(client.get_block_number().await?.as_u64())
+}
+
+// scan the blocks with enough confirmations since the cursor of the subscription.
+async fn scan_once(subscription: &WebhookSubscription, latest_block: u64) -> Result<(), Error> {
+    let confirmed_block = latest_block.saturating_sub(subscription.confirmations);
+    let mut from_block = subscription.next_block;
+    let contract: Address = parse_address(&subscription.contract_address)?;
+    let stake_contract = match subscription.event_type {
+        WebhookEventType::Stake => Some(stake_contract(subscription.network)?),
+        _ => None,
+    };
+    while from_block <= confirmed_block {
+        let to_block = (from_block + NFT_TRANSFER_LOG_BLOCK_RANGE - 1).min(confirmed_block);
+        let transfers = match subscription.event_type {
+            WebhookEventType::Erc1155Transfer => {
+                get_erc1155_transfers(subscription.network, contract, from_block, to_block).await?
+            }
+            WebhookEventType::Erc20Transfer | WebhookEventType::Stake => {
+                get_erc20_transfers(subscription.network, contract, from_block, to_block).await?
+            }
+        };
+        let payloads = build_payloads(subscription, &transfers, stake_contract);
+        enqueue_deliveries(&subscription.id, to_block + 1, payloads).await;
+        from_block = to_block + 1;
+    }
+    Ok(())
+}
+
+async fn deliver_due() {
+    let deliveries = get_due_deliveries(now_secs()).await;
+    spawn_all(
+        deliveries
+            .into_iter()
+            .map(|(delivery, url, secret)| async move {
+                let result = send_payload(&url, &secret, &delivery.payload).await;
+                if let Err((_, err)) = &result {
+                    warn!("deliver webhook {} failed: {}", delivery.id, err);
+                }
+                record_attempt(&delivery.id, result).await;
+                Ok::<_, Error>(())
+            }),
+    )
+    .await;
+}